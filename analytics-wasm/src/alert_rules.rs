@@ -0,0 +1,884 @@
+// ============================================================================
+// Instructor Alert Rules (a small rule language over the gradebook)
+// ============================================================================
+//
+// A rule is a boolean expression of metric comparisons:
+//
+//   missing_streak(lab) >= 2
+//   drop("midterm") > 15
+//   average(quiz, since "2024-10-01") < 60 and not missing == 0
+//
+// Metric arguments are a category (quiz, exam, homework, lab, project, other),
+// a quoted assignment-name fragment, `since "DATE"` / `before "DATE"` on due
+// dates, and for some metrics a number.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::assessment_types::{infer_assessment_type, KNOWN_TYPES};
+use crate::dates::parse_timestamp;
+use crate::submission::{resolve_as_of, submission_records, SubmissionStatus};
+use crate::{calculate_mean, index_grades, Assignment, Grade};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlertRule {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub expression: String,
+    #[serde(default = "default_severity")]
+    pub severity: String,             // "info", "warning", "critical"
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+fn default_severity() -> String {
+    "warning".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AlertRuleOptions {
+    pub type_overrides: HashMap<String, String>,   // assignment_id -> category
+    pub as_of: Option<String>,                     // "Today"; work due later is not yet expected
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RuleError {
+    pub rule_id: String,
+    pub message: String,
+    pub column: usize,                // 1-based position in the expression
+    pub excerpt: String,              // Expression with a caret under the problem
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Alert {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub expression: String,
+    pub student_id: String,
+    pub severity: String,
+    pub message: String,
+    pub evidence: Vec<String>,        // Comparisons that held, with the student's values
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AlertRulesResult {
+    pub alerts: Vec<Alert>,
+    pub errors: Vec<RuleError>,       // Rules that failed to parse are skipped
+    pub rules_evaluated: usize,
+    pub students_alerted: usize,
+}
+
+#[wasm_bindgen]
+pub fn validate_alert_rules(rules_json: &str) -> Result<String, JsValue> {
+    let rules: Vec<AlertRule> = serde_json::from_str(rules_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse rules: {}", e)))?;
+
+    let errors: Vec<RuleError> = rules.iter()
+        .filter_map(|rule| parse_rule(rule).err())
+        .collect();
+
+    serde_json::to_string(&errors)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+#[wasm_bindgen]
+pub fn evaluate_alert_rules(
+    grades_json: &str,
+    assignments_json: &str,
+    rules_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let rules: Vec<AlertRule> = serde_json::from_str(rules_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse rules: {}", e)))?;
+
+    let options: AlertRuleOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = run_alert_rules(&grades, &assignments, &rules, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ----------------------------------------------------------------------------
+// Syntax
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Comparison {
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+        }
+    }
+
+    fn holds(self, left: f64, right: f64) -> bool {
+        match self {
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+            Comparison::Eq => (left - right).abs() < 1e-9,
+            Comparison::Ne => (left - right).abs() >= 1e-9,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    LParen,
+    RParen,
+    Comma,
+    Compare(Comparison),
+    And,
+    Or,
+    Not,
+    End,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(name) => format!("`{}`", name),
+            Token::Number(value) => format!("number {}", value),
+            Token::Str(text) => format!("\"{}\"", text),
+            Token::LParen => "`(`".to_string(),
+            Token::RParen => "`)`".to_string(),
+            Token::Comma => "`,`".to_string(),
+            Token::Compare(op) => format!("`{}`", op.symbol()),
+            Token::And => "`and`".to_string(),
+            Token::Or => "`or`".to_string(),
+            Token::Not => "`not`".to_string(),
+            Token::End => "the end of the rule".to_string(),
+        }
+    }
+}
+
+// Token with its [start, end) character range
+struct Spanned {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+type SyntaxError = (String, usize);
+
+fn tokenize(source: &[char]) -> Result<Vec<Spanned>, SyntaxError> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < source.len() {
+        let c = source[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let token = if c.is_ascii_digit() || (c == '.' && source.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            while i < source.len() && (source[i].is_ascii_digit() || source[i] == '.') {
+                i += 1;
+            }
+            let text: String = source[start..i].iter().collect();
+            let value = text.parse::<f64>()
+                .map_err(|_| (format!("`{}` is not a valid number", text), start))?;
+            // "60%" reads the same as 60
+            if source.get(i) == Some(&'%') {
+                i += 1;
+            }
+            Token::Number(value)
+        } else if c.is_alphabetic() || c == '_' {
+            while i < source.len() && (source[i].is_alphanumeric() || source[i] == '_') {
+                i += 1;
+            }
+            let word: String = source[start..i].iter().collect::<String>().to_lowercase();
+            match word.as_str() {
+                "and" => Token::And,
+                "or" => Token::Or,
+                "not" => Token::Not,
+                _ => Token::Ident(word),
+            }
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            while i < source.len() && source[i] != c {
+                i += 1;
+            }
+            if i == source.len() {
+                return Err(("unterminated string; add a closing quote".to_string(), start));
+            }
+            i += 1;
+            Token::Str(source[start + 1..i - 1].iter().collect())
+        } else {
+            let next = source.get(i + 1).copied();
+            let (token, width) = match (c, next) {
+                ('(', _) => (Token::LParen, 1),
+                (')', _) => (Token::RParen, 1),
+                (',', _) => (Token::Comma, 1),
+                ('<', Some('=')) => (Token::Compare(Comparison::Le), 2),
+                ('<', _) => (Token::Compare(Comparison::Lt), 1),
+                ('>', Some('=')) => (Token::Compare(Comparison::Ge), 2),
+                ('>', _) => (Token::Compare(Comparison::Gt), 1),
+                ('=', Some('=')) => (Token::Compare(Comparison::Eq), 2),
+                ('=', _) => (Token::Compare(Comparison::Eq), 1),
+                ('!', Some('=')) => (Token::Compare(Comparison::Ne), 2),
+                ('!', _) => (Token::Not, 1),
+                ('&', Some('&')) => (Token::And, 2),
+                ('|', Some('|')) => (Token::Or, 2),
+                _ => return Err((format!("unexpected character `{}`", c), start)),
+            };
+            i += width;
+            token
+        };
+
+        tokens.push(Spanned { token, start, end: i });
+    }
+
+    tokens.push(Spanned { token: Token::End, start: source.len(), end: source.len() });
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Metric {
+    Average,
+    RecentAverage,
+    Missing,
+    Late,
+    MissingStreak,
+    LowStreak,
+    Drop,
+    Count,
+    DaysSinceSubmission,
+}
+
+// name, metric, numeric argument it requires, whether it takes filters
+const METRICS: &[(&str, Metric, Option<&str>, bool)] = &[
+    ("average", Metric::Average, None, true),
+    ("recent_average", Metric::RecentAverage, Some("how many recent grades"), true),
+    ("missing", Metric::Missing, None, true),
+    ("late", Metric::Late, None, true),
+    ("missing_streak", Metric::MissingStreak, None, true),
+    ("low_streak", Metric::LowStreak, Some("a score threshold"), true),
+    ("drop", Metric::Drop, None, true),
+    ("count", Metric::Count, None, true),
+    ("days_since_submission", Metric::DaysSinceSubmission, None, false),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Category(String),
+    NameContains(String),
+    Since(f64),
+    Before(f64),
+}
+
+#[derive(Debug)]
+struct MetricCall {
+    metric: Metric,
+    number: Option<f64>,
+    filters: Vec<Filter>,
+    text: String,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Compare { call: MetricCall, op: Comparison, value: f64 },
+}
+
+// Deepest nesting of parentheses and `not` a rule may use
+const MAX_NESTING: usize = 32;
+
+struct Parser<'a> {
+    source: &'a [char],
+    tokens: Vec<Spanned>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Spanned {
+        &self.tokens[self.position]
+    }
+
+    fn advance(&mut self) -> &Spanned {
+        let index = self.position;
+        if self.position + 1 < self.tokens.len() {
+            self.position += 1;
+        }
+        &self.tokens[index]
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, SyntaxError> {
+        Err((message, self.peek().start))
+    }
+
+    // Guard the recursive descent against stack exhaustion
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, SyntaxError>) -> Result<T, SyntaxError> {
+        if self.depth >= MAX_NESTING {
+            return self.error(format!("rule is nested more than {} levels deep", MAX_NESTING));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, SyntaxError> {
+        let mut terms = vec![self.parse_and()?];
+        while self.peek().token == Token::Or {
+            self.advance();
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Expr::Or(terms) })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, SyntaxError> {
+        let mut terms = vec![self.parse_not()?];
+        while self.peek().token == Token::And {
+            self.advance();
+            terms.push(self.parse_not()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Expr::And(terms) })
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, SyntaxError> {
+        if self.peek().token == Token::Not {
+            self.advance();
+            return Ok(Expr::Not(Box::new(self.nested(Self::parse_not)?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, SyntaxError> {
+        match self.peek().token.clone() {
+            Token::LParen => {
+                let open = self.advance().start;
+                let inner = self.nested(Self::parse_or)?;
+                if self.peek().token != Token::RParen {
+                    return Err(("this parenthesis is never closed".to_string(), open));
+                }
+                self.advance();
+                Ok(inner)
+            }
+            Token::Ident(_) => self.parse_comparison(),
+            other => self.error(format!(
+                "expected a metric such as average, missing or missing_streak, found {}",
+                other.describe()
+            )),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, SyntaxError> {
+        let call = self.parse_metric_call()?;
+
+        let op = match self.peek().token {
+            Token::Compare(op) => op,
+            ref other => {
+                return self.error(format!(
+                    "expected a comparison (<, <=, >, >=, ==, !=) after `{}`, found {}",
+                    call.text,
+                    other.describe()
+                ))
+            }
+        };
+        self.advance();
+
+        let value = match self.peek().token {
+            Token::Number(value) => value,
+            ref other => {
+                return self.error(format!("expected a number after `{}`, found {}", op.symbol(), other.describe()))
+            }
+        };
+        self.advance();
+
+        Ok(Expr::Compare { call, op, value })
+    }
+
+    fn parse_metric_call(&mut self) -> Result<MetricCall, SyntaxError> {
+        let name_token = self.advance();
+        let (start, name_end) = (name_token.start, name_token.end);
+        let Token::Ident(name) = name_token.token.clone() else {
+            unreachable!("parse_primary only dispatches on identifiers");
+        };
+
+        let Some(&(_, metric, number_hint, takes_filters)) = METRICS.iter().find(|(n, ..)| *n == name) else {
+            let names: Vec<&str> = METRICS.iter().map(|(n, ..)| *n).collect();
+            return Err((format!("unknown metric `{}`{}", name, suggestion(&name, &names)), start));
+        };
+
+        let mut number = None;
+        let mut filters = Vec::new();
+        let mut end = name_end;
+
+        if self.peek().token == Token::LParen {
+            self.advance();
+            loop {
+                let argument = self.advance();
+                let argument_start = argument.start;
+                match argument.token.clone() {
+                    Token::RParen if number.is_none() && filters.is_empty() => {
+                        end = argument.end;
+                        break;
+                    }
+                    Token::Number(value) => {
+                        if number_hint.is_none() {
+                            return Err((format!("`{}` does not take a numeric argument", name), argument_start));
+                        }
+                        if number.replace(value).is_some() {
+                            return Err((format!("`{}` takes only one number", name), argument_start));
+                        }
+                    }
+                    Token::Str(text) if takes_filters => filters.push(Filter::NameContains(text.to_lowercase())),
+                    Token::Ident(word) if takes_filters && (word == "since" || word == "before") => {
+                        let date_token = self.advance();
+                        let Token::Str(date) = date_token.token.clone() else {
+                            return Err((
+                                format!("`{}` needs a quoted date, e.g. {} \"2024-10-01\"", word, word),
+                                date_token.start,
+                            ));
+                        };
+                        let Some(day) = parse_timestamp(&date) else {
+                            return Err((
+                                format!("\"{}\" is not a date; use YYYY-MM-DD", date),
+                                date_token.start,
+                            ));
+                        };
+                        filters.push(if word == "since" { Filter::Since(day) } else { Filter::Before(day) });
+                    }
+                    Token::Ident(word) if takes_filters => {
+                        if !KNOWN_TYPES.contains(&word.as_str()) {
+                            return Err((
+                                format!(
+                                    "unknown category `{}`{}; use one of {} or a quoted name like \"midterm\"",
+                                    word,
+                                    suggestion(&word, KNOWN_TYPES),
+                                    KNOWN_TYPES.join(", ")
+                                ),
+                                argument_start,
+                            ));
+                        }
+                        filters.push(Filter::Category(word));
+                    }
+                    other => {
+                        let message = if takes_filters {
+                            format!("unexpected {} in the arguments of `{}`", other.describe(), name)
+                        } else {
+                            format!("`{}` takes no arguments", name)
+                        };
+                        return Err((message, argument_start));
+                    }
+                }
+
+                let separator = self.advance();
+                match separator.token {
+                    Token::Comma => continue,
+                    Token::RParen => {
+                        end = separator.end;
+                        break;
+                    }
+                    ref other => {
+                        return Err((
+                            format!("expected `,` or `)` in the arguments of `{}`, found {}", name, other.describe()),
+                            separator.start,
+                        ))
+                    }
+                }
+            }
+        }
+
+        if let (Some(hint), None) = (number_hint, number) {
+            return Err((format!("`{}` needs a number ({}), e.g. {}(3)", name, hint, name), start));
+        }
+
+        Ok(MetricCall {
+            metric,
+            number,
+            filters,
+            text: self.source[start..end].iter().collect(),
+        })
+    }
+}
+
+// " (did you mean `x`?)" for near-misses
+fn suggestion(word: &str, candidates: &[&str]) -> String {
+    candidates.iter()
+        .map(|c| (edit_distance(word, c), *c))
+        .filter(|(distance, c)| *distance <= 2 || c.starts_with(word))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| format!(" (did you mean `{}`?)", c))
+        .unwrap_or_default()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for j in 0..b.len() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == b[j] { previous } else { 1 + previous.min(row[j]).min(row[j + 1]) };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+fn parse_rule(rule: &AlertRule) -> Result<Expr, RuleError> {
+    let source: Vec<char> = rule.expression.chars().collect();
+
+    let parsed = tokenize(&source).and_then(|tokens| {
+        let mut parser = Parser { source: &source, tokens, position: 0, depth: 0 };
+        if parser.peek().token == Token::End {
+            return parser.error("rule is empty".to_string());
+        }
+        let expr = parser.parse_or()?;
+        match &parser.peek().token {
+            Token::End => Ok(expr),
+            other => parser.error(format!(
+                "unexpected {} after a complete condition; join conditions with `and` / `or`",
+                other.describe()
+            )),
+        }
+    });
+
+    parsed.map_err(|(message, position)| RuleError {
+        rule_id: rule.id.clone(),
+        message,
+        column: position + 1,
+        excerpt: format!("{}\n{}^", rule.expression, " ".repeat(position)),
+    })
+}
+
+// ----------------------------------------------------------------------------
+// Evaluation
+// ----------------------------------------------------------------------------
+
+// One expected assignment for a student, in course order
+struct StudentItem {
+    name: String,
+    category: String,
+    due: Option<f64>,
+    status: SubmissionStatus,
+    percentage: Option<f64>,
+    submitted: Option<f64>,
+}
+
+fn matches_filters(item: &StudentItem, filters: &[Filter]) -> bool {
+    filters.iter().all(|filter| match filter {
+        Filter::Category(category) => item.category == *category,
+        Filter::NameContains(text) => item.name.to_lowercase().contains(text),
+        Filter::Since(day) => item.due.is_some_and(|due| due >= *day),
+        Filter::Before(day) => item.due.is_some_and(|due| due < *day),
+    })
+}
+
+fn longest_run(items: &[&StudentItem], predicate: impl Fn(&StudentItem) -> bool) -> f64 {
+    let mut longest = 0;
+    let mut current = 0;
+    for item in items {
+        if predicate(item) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest as f64
+}
+
+fn evaluate_metric(call: &MetricCall, items: &[StudentItem], as_of: Option<f64>) -> Option<f64> {
+    let selected: Vec<&StudentItem> = items.iter().filter(|i| matches_filters(i, &call.filters)).collect();
+    let scores: Vec<f64> = selected.iter().filter_map(|i| i.percentage).collect();
+    let count = |status: SubmissionStatus| selected.iter().filter(|i| i.status == status).count() as f64;
+
+    match call.metric {
+        Metric::Average => (!scores.is_empty()).then(|| calculate_mean(&scores)),
+        Metric::RecentAverage => {
+            let n = call.number.unwrap_or(3.0).max(1.0) as usize;
+            (!scores.is_empty()).then(|| calculate_mean(&scores[scores.len().saturating_sub(n)..]))
+        }
+        Metric::Missing => Some(count(SubmissionStatus::Missing)),
+        Metric::Late => Some(count(SubmissionStatus::Late)),
+        Metric::MissingStreak => Some(longest_run(&selected, |i| i.status == SubmissionStatus::Missing)),
+        Metric::LowStreak => {
+            let threshold = call.number.unwrap_or(60.0);
+            Some(longest_run(&selected, |i| {
+                i.status == SubmissionStatus::Missing || i.percentage.is_some_and(|p| p < threshold)
+            }))
+        }
+        Metric::Drop => (scores.len() >= 2).then(|| {
+            scores.windows(2).map(|pair| pair[0] - pair[1]).fold(0.0, f64::max)
+        }),
+        Metric::Count => Some(scores.len() as f64),
+        Metric::DaysSinceSubmission => {
+            let last = items.iter().filter_map(|i| i.submitted).fold(f64::NEG_INFINITY, f64::max);
+            as_of.filter(|_| last.is_finite()).map(|today| today - last)
+        }
+    }
+}
+
+fn evaluate(expr: &Expr, items: &[StudentItem], as_of: Option<f64>, evidence: &mut Vec<String>) -> bool {
+    match expr {
+        Expr::Or(terms) => {
+            let mut any = false;
+            for term in terms {
+                any |= evaluate(term, items, as_of, evidence);
+            }
+            any
+        }
+        Expr::And(terms) => {
+            let mut collected = Vec::new();
+            let all = terms.iter().all(|term| evaluate(term, items, as_of, &mut collected));
+            if all {
+                evidence.extend(collected);
+            }
+            all
+        }
+        Expr::Not(inner) => !evaluate(inner, items, as_of, &mut Vec::new()),
+        Expr::Compare { call, op, value } => {
+            let Some(actual) = evaluate_metric(call, items, as_of) else {
+                return false;
+            };
+            let holds = op.holds(actual, *value);
+            if holds {
+                evidence.push(format!("{} = {:.1} ({} {})", call.text, actual, op.symbol(), value));
+            }
+            holds
+        }
+    }
+}
+
+fn student_items(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &AlertRuleOptions,
+    as_of: Option<f64>,
+) -> BTreeMap<String, Vec<StudentItem>> {
+    let index = index_grades(grades);
+    submission_records(grades, assignments, as_of).into_iter()
+        .map(|(student_id, records)| {
+            let items = records.into_iter()
+                .map(|record| {
+                    let assignment = assignments.iter().find(|a| a.id == record.assignment_id);
+                    let name = assignment.map(|a| a.name.clone()).unwrap_or_else(|| record.assignment_id.clone());
+                    let category = options.type_overrides.get(&record.assignment_id)
+                        .map(|t| t.to_lowercase())
+                        .unwrap_or_else(|| infer_assessment_type(&name));
+                    let attempts: &[&Grade] = index.get(&(student_id.as_str(), record.assignment_id.as_str()))
+                        .map_or(&[], |list| list);
+
+                    StudentItem {
+                        percentage: attempts.iter()
+                            .filter(|g| g.max_score > 0.0)
+                            .map(|g| (g.score / g.max_score) * 100.0)
+                            .reduce(f64::max),
+                        submitted: attempts.iter()
+                            .filter_map(|g| g.submitted_at.as_deref().and_then(parse_timestamp))
+                            .reduce(f64::max),
+                        name,
+                        category,
+                        due: record.due_day,
+                        status: record.status,
+                    }
+                })
+                .collect();
+            (student_id, items)
+        })
+        .collect()
+}
+
+pub(crate) fn run_alert_rules(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    rules: &[AlertRule],
+    options: &AlertRuleOptions,
+) -> AlertRulesResult {
+    let mut errors = Vec::new();
+    let parsed: Vec<(&AlertRule, Expr)> = rules.iter()
+        .filter_map(|rule| match parse_rule(rule) {
+            Ok(expr) => Some((rule, expr)),
+            Err(error) => {
+                errors.push(error);
+                None
+            }
+        })
+        .collect();
+
+    // Default "today" is the latest submission seen anywhere in the course
+    let as_of = resolve_as_of(grades, options.as_of.as_deref());
+    let students = student_items(grades, assignments, options, as_of);
+
+    let mut alerts = Vec::new();
+    for (rule, expr) in &parsed {
+        let rule_name = rule.name.clone().unwrap_or_else(|| rule.id.clone());
+        for (student_id, items) in &students {
+            let mut evidence = Vec::new();
+            if evaluate(expr, items, as_of, &mut evidence) {
+                alerts.push(Alert {
+                    rule_id: rule.id.clone(),
+                    rule_name: rule_name.clone(),
+                    expression: rule.expression.clone(),
+                    student_id: student_id.clone(),
+                    severity: rule.severity.clone(),
+                    message: rule.message.clone()
+                        .unwrap_or_else(|| format!("Rule \"{}\" matched: {}", rule_name, rule.expression)),
+                    evidence,
+                });
+            }
+        }
+    }
+
+    let mut alerted: Vec<&str> = alerts.iter().map(|a| a.student_id.as_str()).collect();
+    alerted.sort();
+    alerted.dedup();
+
+    AlertRulesResult {
+        students_alerted: alerted.len(),
+        rules_evaluated: parsed.len(),
+        alerts,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, expression: &str) -> AlertRule {
+        AlertRule {
+            id: id.to_string(),
+            name: None,
+            expression: expression.to_string(),
+            severity: default_severity(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_problem() {
+        let error = parse_rule(&rule("r1", "avrage < 60")).unwrap_err();
+        assert_eq!(error.column, 1);
+        assert!(error.message.contains("did you mean `average`"));
+
+        let error = parse_rule(&rule("r2", "missing_streak(labs) >= 2")).unwrap_err();
+        assert_eq!(error.column, 16);
+        assert!(error.message.contains("did you mean `lab`"));
+
+        let error = parse_rule(&rule("r3", "average(quiz) 60")).unwrap_err();
+        assert!(error.message.contains("expected a comparison"));
+        assert_eq!(error.excerpt, "average(quiz) 60\n              ^");
+
+        assert!(parse_rule(&rule("r4", "recent_average < 50")).unwrap_err().message.contains("needs a number"));
+        assert!(parse_rule(&rule("r5", "(missing > 1 or late >= 2")).is_err());
+        assert!(parse_rule(&rule("r6", "average(quiz, since \"2024-10-01\") < 60% and not missing == 0")).is_ok());
+
+        let deep = format!("{}missing > 1{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert!(parse_rule(&rule("r7", &deep)).unwrap_err().message.contains("nested more than"));
+        let negated = format!("{}missing > 1", "not ".repeat(10_000));
+        assert!(parse_rule(&rule("r8", &negated)).is_err());
+        assert!(parse_rule(&rule("r9", &format!("{}missing > 1", "not ".repeat(8)))).is_ok());
+    }
+
+    #[test]
+    fn test_rules_fire_with_evidence() {
+        let assignments: Vec<Assignment> = ["Lab 1", "Lab 2", "Midterm 1", "Lab 3", "Midterm 2"].iter()
+            .enumerate()
+            .map(|(i, name)| Assignment {
+                id: format!("A{}", i + 1),
+                name: name.to_string(),
+                max_score: 100.0,
+                due_date: Some(format!("2024-10-{:02}", (i + 1) * 5)),
+            })
+            .collect();
+        let grade = |student: &str, assignment: &str, score: f64| Grade {
+            student_id: student.to_string(),
+            assignment_id: assignment.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        };
+        let grades = vec![
+            grade("S1", "A1", 80.0), grade("S1", "A3", 85.0), grade("S1", "A5", 62.0),
+            grade("S2", "A1", 90.0), grade("S2", "A2", 88.0), grade("S2", "A3", 70.0),
+            grade("S2", "A4", 91.0), grade("S2", "A5", 75.0),
+        ];
+        let rules = vec![
+            rule("labs", "missing_streak(lab) >= 2"),
+            rule("midterms", "drop(\"midterm\") > 15"),
+            rule("broken", "average <"),
+        ];
+
+        let result = run_alert_rules(&grades, &assignments, &rules, &AlertRuleOptions::default());
+
+        assert_eq!(result.rules_evaluated, 2);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].rule_id, "broken");
+        assert_eq!(result.alerts.len(), 2);
+        assert!(result.alerts.iter().all(|a| a.student_id == "S1"));
+        assert_eq!(result.alerts[0].evidence, vec!["missing_streak(lab) = 2.0 (>= 2)".to_string()]);
+        assert_eq!(result.alerts[1].rule_id, "midterms");
+        assert_eq!(result.students_alerted, 1);
+    }
+
+    #[test]
+    fn test_future_work_is_not_missing() {
+        let assignments: Vec<Assignment> = ["2024-10-05", "2024-10-12", "2024-12-20"].iter()
+            .enumerate()
+            .map(|(i, due)| Assignment {
+                id: format!("A{}", i + 1),
+                name: format!("Lab {}", i + 1),
+                max_score: 100.0,
+                due_date: Some(due.to_string()),
+            })
+            .collect();
+        let grade = |assignment: &str, score: f64, submitted: &str| Grade {
+            student_id: "S1".to_string(),
+            assignment_id: assignment.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: Some(submitted.to_string()),
+            due_date: None,
+        };
+        let grades = vec![grade("A1", 50.0, "2024-10-04"), grade("A2", 40.0, "2024-10-11")];
+        let rules = vec![rule("missing", "missing >= 1"), rule("low", "low_streak(60) >= 3")];
+
+        let result = run_alert_rules(&grades, &assignments, &rules, &AlertRuleOptions::default());
+        assert!(result.alerts.is_empty());
+
+        let options = AlertRuleOptions { as_of: Some("2024-12-21".to_string()), ..Default::default() };
+        let result = run_alert_rules(&grades, &assignments, &rules, &options);
+        assert_eq!(result.alerts.len(), 2);
+    }
+}
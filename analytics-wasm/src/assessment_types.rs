@@ -0,0 +1,312 @@
+// ============================================================================
+// Assessment Type Effectiveness (quiz vs exam vs homework vs lab vs project)
+// ============================================================================
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::assignment_timeline;
+use crate::{
+    analyze_item, calculate_correlation, calculate_cronbachs_alpha, calculate_mean,
+    calculate_std_deviation, calculate_quantile, Assignment, Grade,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AssessmentTypeOptions {
+    pub type_overrides: HashMap<String, String>,   // assignment_id -> type, beats name inference
+    pub final_exam_id: Option<String>,             // Defaults to the last "final" exam by due date
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AssessmentTypeStats {
+    pub assessment_type: String,
+    pub item_count: usize,
+    pub assignment_ids: Vec<String>,
+    pub internal_alpha: f64,              // Cronbach's alpha within the type
+    pub alpha_without_type: f64,          // Whole-course alpha with this type removed
+    pub reliability_contribution: f64,    // Course alpha minus alpha_without_type
+    pub final_exam_correlation: Option<f64>,
+    pub students_compared: usize,
+    pub mean_discrimination: f64,
+    pub mean_difficulty: f64,
+    pub mean_score: f64,
+    pub std_deviation: f64,
+    pub interquartile_range: f64,
+    pub verdict: String,                  // "strong", "mixed", "weak", "insufficient_data"
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AssessmentTypeResult {
+    pub types: Vec<AssessmentTypeStats>,
+    pub overall_alpha: f64,
+    pub final_exam: Option<String>,
+    pub total_items: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_assessment_types(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: AssessmentTypeOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = compute_assessment_types(&grades, &assignments, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+pub(crate) const KNOWN_TYPES: &[&str] = &["quiz", "exam", "homework", "lab", "project", "other"];
+
+// Infer the assessment type from words in the assignment name
+pub(crate) fn infer_assessment_type(assignment_name: &str) -> String {
+    let lower = assignment_name.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let has = |candidates: &[&str]| words.iter().any(|w| candidates.contains(w));
+
+    // Bare "final" and "test" only mean an exam when nothing more specific
+    // does ("Final Project", "Lab Test")
+    if has(&["exam", "exams", "midterm"]) {
+        "exam".to_string()
+    } else if has(&["quiz", "quizzes"]) {
+        "quiz".to_string()
+    } else if has(&["lab", "labs", "laboratory", "practical"]) {
+        "lab".to_string()
+    } else if has(&["project", "capstone"]) {
+        "project".to_string()
+    } else if has(&["final", "test"]) {
+        "exam".to_string()
+    } else if has(&["homework", "hw", "assignment", "problem", "pset", "exercise", "worksheet"]) {
+        "homework".to_string()
+    } else {
+        "other".to_string()
+    }
+}
+
+pub(crate) fn assessment_type(assignment: &Assignment, overrides: &HashMap<String, String>) -> String {
+    overrides.get(&assignment.id)
+        .map(|t| t.to_lowercase())
+        .unwrap_or_else(|| infer_assessment_type(&assignment.name))
+}
+
+pub(crate) fn compute_assessment_types(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &AssessmentTypeOptions,
+) -> AssessmentTypeResult {
+    let overall_alpha = calculate_cronbachs_alpha(grades, assignments);
+
+    // Final exam: explicit, else the last-due exam named "final", else the last-due exam
+    let exams: Vec<&Assignment> = assignment_timeline(grades, assignments).iter()
+        .filter_map(|(id, _)| assignments.iter().find(|a| a.id == *id))
+        .filter(|a| assessment_type(a, &options.type_overrides) == "exam")
+        .collect();
+    let final_exam: Option<&Assignment> = match &options.final_exam_id {
+        Some(id) => assignments.iter().find(|a| a.id == *id),
+        None => exams.iter()
+            .rev()
+            .find(|a| a.name.to_lowercase().contains("final"))
+            .or(exams.last())
+            .copied(),
+    };
+
+    let final_scores: BTreeMap<&str, f64> = final_exam
+        .map(|exam| {
+            grades.iter()
+                .filter(|g| g.assignment_id == exam.id && g.max_score > 0.0)
+                .map(|g| (g.student_id.as_str(), (g.score / g.max_score) * 100.0))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut by_type: BTreeMap<String, Vec<Assignment>> = BTreeMap::new();
+    for assignment in assignments {
+        by_type
+            .entry(assessment_type(assignment, &options.type_overrides))
+            .or_default()
+            .push(assignment.clone());
+    }
+
+    let types: Vec<AssessmentTypeStats> = by_type.iter()
+        .map(|(assessment_type, items)| {
+            let others: Vec<Assignment> = assignments.iter()
+                .filter(|a| !items.iter().any(|i| i.id == a.id))
+                .cloned()
+                .collect();
+            let alpha_without_type = calculate_cronbachs_alpha(grades, &others);
+
+            let analyses: Vec<_> = items.iter()
+                .map(|item| analyze_item(grades, item))
+                .filter(|analysis| analysis.quality_rating != "insufficient_data")
+                .collect();
+
+            let mut scores: Vec<f64> = grades.iter()
+                .filter(|g| g.max_score > 0.0 && items.iter().any(|i| i.id == g.assignment_id))
+                .map(|g| (g.score / g.max_score) * 100.0)
+                .collect();
+            scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let mean_score = calculate_mean(&scores);
+
+            // Predictive validity: type average vs final exam, final itself excluded
+            let mut per_student: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+            for grade in grades.iter().filter(|g| g.max_score > 0.0) {
+                let counted = items.iter().any(|i| i.id == grade.assignment_id)
+                    && final_exam.is_none_or(|exam| exam.id != grade.assignment_id);
+                if counted {
+                    per_student
+                        .entry(grade.student_id.as_str())
+                        .or_default()
+                        .push((grade.score / grade.max_score) * 100.0);
+                }
+            }
+            let (type_means, finals): (Vec<f64>, Vec<f64>) = per_student.iter()
+                .filter_map(|(student_id, scores)| {
+                    final_scores.get(student_id).map(|f| (calculate_mean(scores), *f))
+                })
+                .unzip();
+            let final_exam_correlation = (type_means.len() >= 3)
+                .then(|| calculate_correlation(&type_means, &finals));
+
+            let reliability_contribution = if assignments.len() > items.len() {
+                overall_alpha - alpha_without_type
+            } else {
+                0.0
+            };
+            let mean_discrimination = calculate_mean(
+                &analyses.iter().map(|a| a.discrimination_index).collect::<Vec<_>>(),
+            );
+
+            AssessmentTypeStats {
+                assessment_type: assessment_type.clone(),
+                item_count: items.len(),
+                assignment_ids: items.iter().map(|i| i.id.clone()).collect(),
+                internal_alpha: calculate_cronbachs_alpha(grades, items),
+                alpha_without_type,
+                reliability_contribution,
+                final_exam_correlation,
+                students_compared: type_means.len(),
+                mean_discrimination,
+                mean_difficulty: calculate_mean(
+                    &analyses.iter().map(|a| a.difficulty_index).collect::<Vec<_>>(),
+                ),
+                mean_score,
+                std_deviation: calculate_std_deviation(&scores, mean_score),
+                interquartile_range: calculate_quantile(&scores, 0.75) - calculate_quantile(&scores, 0.25),
+                verdict: rate_type_effectiveness(
+                    analyses.len(),
+                    reliability_contribution,
+                    final_exam_correlation,
+                    mean_discrimination,
+                ),
+            }
+        })
+        .collect();
+
+    AssessmentTypeResult {
+        total_items: assignments.len(),
+        types,
+        overall_alpha,
+        final_exam: final_exam.map(|a| a.id.clone()),
+    }
+}
+
+// Count how many of reliability, prediction and discrimination a type delivers on
+fn rate_type_effectiveness(
+    analyzed_items: usize,
+    reliability_contribution: f64,
+    final_exam_correlation: Option<f64>,
+    mean_discrimination: f64,
+) -> String {
+    if analyzed_items == 0 {
+        return "insufficient_data".to_string();
+    }
+
+    let signals = [
+        reliability_contribution > 0.0,
+        final_exam_correlation.is_some_and(|r| r >= 0.3),
+        mean_discrimination >= 0.3,
+    ];
+    match signals.iter().filter(|s| **s).count() {
+        3 => "strong".to_string(),
+        2 => "mixed".to_string(),
+        _ => "weak".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_assessment_type() {
+        assert_eq!(infer_assessment_type("Chapter 3 Quiz"), "quiz");
+        assert_eq!(infer_assessment_type("Final Exam"), "exam");
+        assert_eq!(infer_assessment_type("HW 4: Recursion"), "homework");
+        assert_eq!(infer_assessment_type("Lab 2 - Circuits"), "lab");
+        assert_eq!(infer_assessment_type("Final Project"), "project");
+        assert_eq!(infer_assessment_type("Final Lab Report"), "lab");
+        assert_eq!(infer_assessment_type("Lab Test"), "lab");
+        assert_eq!(infer_assessment_type("Unit Test 2"), "exam");
+        assert_eq!(infer_assessment_type("Syllabus acknowledgement"), "other");
+    }
+
+    #[test]
+    fn test_quiz_predicts_final_exam() {
+        let names = ["Quiz 1", "Quiz 2", "Homework 1", "Final Exam"];
+        let assignments: Vec<Assignment> = names.iter()
+            .enumerate()
+            .map(|(i, name)| Assignment {
+                id: format!("A{}", i + 1),
+                name: name.to_string(),
+                max_score: 100.0,
+                due_date: Some(format!("2024-0{}-01", i + 1)),
+            })
+            .collect();
+        // Quizzes track the final; homework is flat
+        let rows = [
+            ("S1", [90.0, 85.0, 95.0, 92.0]),
+            ("S2", [70.0, 75.0, 94.0, 71.0]),
+            ("S3", [50.0, 55.0, 96.0, 52.0]),
+            ("S4", [80.0, 78.0, 95.0, 83.0]),
+        ];
+        let grades: Vec<Grade> = rows.iter()
+            .flat_map(|(student, scores)| {
+                scores.iter().enumerate().map(move |(i, score)| Grade {
+                    student_id: student.to_string(),
+                    assignment_id: format!("A{}", i + 1),
+                    score: *score,
+                    max_score: 100.0,
+                    submitted_at: None,
+                    due_date: None,
+                })
+            })
+            .collect();
+
+        let result = compute_assessment_types(&grades, &assignments, &AssessmentTypeOptions::default());
+
+        assert_eq!(result.final_exam.as_deref(), Some("A4"));
+        let quiz = result.types.iter().find(|t| t.assessment_type == "quiz").unwrap();
+        let homework = result.types.iter().find(|t| t.assessment_type == "homework").unwrap();
+
+        assert_eq!(quiz.item_count, 2);
+        assert!(quiz.final_exam_correlation.unwrap() > 0.95);
+        assert!(homework.final_exam_correlation.unwrap() < quiz.final_exam_correlation.unwrap());
+        assert!(quiz.reliability_contribution > 0.0);
+        assert!(homework.std_deviation < quiz.std_deviation);
+    }
+}
@@ -0,0 +1,297 @@
+// ============================================================================
+// Chapter Difficulty Statistics (bootstrap intervals and pairwise tests)
+// ============================================================================
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::random::SeededRng;
+use crate::{calculate_mean, calculate_quantile};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChapterTest {
+    Welch,        // Independent samples, unequal variances
+    Paired,       // Per-student chapter averages over shared students
+    Auto,         // Paired when enough students took both chapters
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ChapterStatisticsOptions {
+    pub bootstrap_samples: usize,
+    pub confidence_level: f64,        // For the mean's interval
+    pub alpha: f64,                   // Family-wise significance level after Holm
+    pub test: ChapterTest,
+    pub min_paired_students: usize,
+    pub seed: u64,
+}
+
+impl Default for ChapterStatisticsOptions {
+    fn default() -> Self {
+        ChapterStatisticsOptions {
+            bootstrap_samples: 2000,
+            confidence_level: 0.95,
+            alpha: 0.05,
+            test: ChapterTest::Auto,
+            min_paired_students: 5,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChapterComparison {
+    pub chapter_a: String,
+    pub chapter_b: String,
+    pub mean_difference: f64,         // a minus b in per-student averages, percentage points
+    pub test: String,                 // "welch" or "paired"
+    pub t_statistic: Option<f64>,     // None when the difference has no spread at all
+    pub degrees_of_freedom: f64,
+    pub p_value: f64,
+    pub adjusted_p_value: f64,        // Holm across all pairs
+    pub significant: bool,
+    pub sample_size: usize,           // Shared students for paired tests, students in either chapter otherwise
+}
+
+// Per-student averages for one chapter. Tests run on these rather than raw
+// grades, since a student's grades within a chapter are not independent.
+pub(crate) struct ChapterSample {
+    pub name: String,
+    pub student_means: BTreeMap<String, f64>,
+}
+
+// Percentile bootstrap interval for the mean
+pub(crate) fn bootstrap_mean_interval(
+    scores: &[f64],
+    options: &ChapterStatisticsOptions,
+    rng: &mut SeededRng,
+) -> Option<(f64, f64)> {
+    if scores.len() < 2 || options.bootstrap_samples == 0 {
+        return None;
+    }
+    let mut means: Vec<f64> = (0..options.bootstrap_samples)
+        .map(|_| {
+            let total: f64 = (0..scores.len()).map(|_| scores[rng.next_index(scores.len())]).sum();
+            total / scores.len() as f64
+        })
+        .collect();
+    means.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let tail = (1.0 - options.confidence_level.clamp(0.0, 1.0)) / 2.0;
+    Some((calculate_quantile(&means, tail), calculate_quantile(&means, 1.0 - tail)))
+}
+
+// Every pair of chapters, Holm-adjusted as one family
+pub(crate) fn compare_chapters(
+    samples: &[ChapterSample],
+    options: &ChapterStatisticsOptions,
+) -> Vec<ChapterComparison> {
+    let mut comparisons = Vec::new();
+    for (i, a) in samples.iter().enumerate() {
+        for b in &samples[i + 1..] {
+            let shared: Vec<f64> = a.student_means.iter()
+                .filter_map(|(student, x)| b.student_means.get(student).map(|y| x - y))
+                .collect();
+            let paired = match options.test {
+                ChapterTest::Paired => true,
+                ChapterTest::Welch => false,
+                ChapterTest::Auto => shared.len() >= options.min_paired_students.max(2),
+            };
+            let (test, result, mean_difference, sample_size) = if paired {
+                ("paired", paired_t_test(&shared), calculate_mean(&shared), shared.len())
+            } else {
+                let xs: Vec<f64> = a.student_means.values().copied().collect();
+                let ys: Vec<f64> = b.student_means.values().copied().collect();
+                let difference = calculate_mean(&xs) - calculate_mean(&ys);
+                ("welch", welch_t_test(&xs, &ys), difference, xs.len() + ys.len())
+            };
+            // Too few students to estimate a variance
+            let Some((t_statistic, degrees_of_freedom, p_value)) = result else {
+                continue;
+            };
+            comparisons.push(ChapterComparison {
+                chapter_a: a.name.clone(),
+                chapter_b: b.name.clone(),
+                mean_difference,
+                test: test.to_string(),
+                t_statistic: t_statistic.is_finite().then_some(t_statistic),
+                degrees_of_freedom,
+                p_value,
+                adjusted_p_value: p_value,
+                significant: false,
+                sample_size,
+            });
+        }
+    }
+
+    let adjusted = holm_adjust(&comparisons.iter().map(|c| c.p_value).collect::<Vec<_>>());
+    for (comparison, p) in comparisons.iter_mut().zip(adjusted) {
+        comparison.adjusted_p_value = p;
+        comparison.significant = p < options.alpha;
+    }
+    comparisons
+}
+
+// (t, df, two-sided p); None when either sample is too small for a variance
+pub(crate) fn welch_t_test(a: &[f64], b: &[f64]) -> Option<(f64, f64, f64)> {
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+    let variance = |xs: &[f64]| {
+        let mean = calculate_mean(xs);
+        xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (xs.len() - 1) as f64
+    };
+    let (va, vb) = (variance(a) / a.len() as f64, variance(b) / b.len() as f64);
+    let difference = calculate_mean(a) - calculate_mean(b);
+    let standard_error = (va + vb).sqrt();
+    if standard_error == 0.0 {
+        return Some(degenerate_t(difference, (a.len() + b.len() - 2) as f64));
+    }
+    let t = difference / standard_error;
+    let df = (va + vb).powi(2)
+        / (va.powi(2) / (a.len() - 1) as f64 + vb.powi(2) / (b.len() - 1) as f64);
+    Some((t, df, student_t_two_sided(t, df)))
+}
+
+pub(crate) fn paired_t_test(differences: &[f64]) -> Option<(f64, f64, f64)> {
+    if differences.len() < 2 {
+        return None;
+    }
+    let n = differences.len() as f64;
+    let mean = calculate_mean(differences);
+    let variance = differences.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    if variance == 0.0 {
+        return Some(degenerate_t(mean, n - 1.0));
+    }
+    let t = mean / (variance / n).sqrt();
+    Some((t, n - 1.0, student_t_two_sided(t, n - 1.0)))
+}
+
+// No spread at all: any difference is certain, none is no evidence
+fn degenerate_t(difference: f64, df: f64) -> (f64, f64, f64) {
+    if difference == 0.0 {
+        (0.0, df, 1.0)
+    } else {
+        (f64::INFINITY.copysign(difference), df, 0.0)
+    }
+}
+
+// Holm step-down adjustment, returned in the input order
+pub(crate) fn holm_adjust(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|a, b| p_values[*a].partial_cmp(&p_values[*b]).unwrap());
+
+    let mut adjusted = vec![0.0; m];
+    let mut running_max: f64 = 0.0;
+    for (rank, index) in order.iter().enumerate() {
+        running_max = running_max.max(((m - rank) as f64 * p_values[*index]).min(1.0));
+        adjusted[*index] = running_max;
+    }
+    adjusted
+}
+
+// P(|T| >= |t|) for Student's t with df degrees of freedom
+pub(crate) fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    if !t.is_finite() || df <= 0.0 {
+        return if t.is_finite() { 1.0 } else { 0.0 };
+    }
+    regularized_incomplete_beta(df / (df + t * t), df / 2.0, 0.5).clamp(0.0, 1.0)
+}
+
+// Standard normal CDF (Abramowitz-Stegun 7.1.26 erf)
+pub(crate) fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
+
+// Lanczos approximation of ln(Gamma(x))
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46, -86.505_320_329_416_77, 24.014_098_240_830_91,
+        -1.231_739_572_450_155, 0.001_208_650_973_866_179, -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let series: f64 = COEFFICIENTS.iter()
+        .enumerate()
+        .map(|(j, c)| c / (x + 1.0 + j as f64))
+        .sum::<f64>() + 1.000_000_000_190_015;
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+// I_x(a, b) via Lentz's continued fraction
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..200 {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        for coefficient in [even, -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0))] {
+            d = 1.0 + coefficient * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + coefficient / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_t_distribution_and_holm() {
+        // t = 2.228 is the two-sided 5% critical value at 10 df
+        assert!((student_t_two_sided(2.228, 10.0) - 0.05).abs() < 1e-3);
+        assert!((student_t_two_sided(0.0, 5.0) - 1.0).abs() < 1e-9);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+
+        let adjusted = holm_adjust(&[0.01, 0.04, 0.03]);
+        assert!((adjusted[0] - 0.03).abs() < 1e-12);
+        assert!((adjusted[2] - 0.06).abs() < 1e-12);
+        assert!((adjusted[1] - 0.06).abs() < 1e-12);
+
+        let (t, _, p) = welch_t_test(&[50.0, 55.0, 52.0, 48.0], &[80.0, 85.0, 82.0, 79.0]).unwrap();
+        assert!(t < 0.0 && p < 0.001);
+
+        // Every student exactly ten points apart is as significant as it gets
+        assert_eq!(paired_t_test(&[10.0, 10.0, 10.0]).unwrap().2, 0.0);
+        assert_eq!(welch_t_test(&[70.0, 70.0], &[70.0, 70.0]).unwrap().2, 1.0);
+        assert!(welch_t_test(&[70.0], &[80.0, 90.0]).is_none());
+    }
+}
@@ -0,0 +1,469 @@
+// ============================================================================
+// Improvement Trajectory Mapping (trajectory clustering)
+// ============================================================================
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::assignment_timeline;
+use crate::{calculate_mean, timeline_trajectory, Assignment, Grade};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClusteringMethod {
+    Kmeans,       // k-means on resampled trajectories
+    Dtw,          // average-linkage hierarchical clustering on DTW distances
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TrajectoryClusteringOptions {
+    pub method: ClusteringMethod,
+    pub clusters: usize,          // Number of archetypes to find
+    pub resample_points: usize,   // Length every trajectory is resampled to
+    pub max_iterations: usize,    // k-means iteration cap
+}
+
+impl Default for TrajectoryClusteringOptions {
+    fn default() -> Self {
+        TrajectoryClusteringOptions {
+            method: ClusteringMethod::Kmeans,
+            clusters: 4,
+            resample_points: 10,
+            max_iterations: 100,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TrajectoryCluster {
+    pub cluster_id: usize,
+    pub archetype: String,        // "late_bloomer", "early_fade", "steady_high", ...
+    pub description: String,
+    pub centroid: Vec<f64>,       // Mean resampled trajectory (0-100)
+    pub members: Vec<String>,
+    pub size: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TrajectoryClusteringResult {
+    pub method: String,
+    pub clusters: Vec<TrajectoryCluster>,
+    pub total_students: usize,
+    pub unclustered_students: Vec<String>,  // Fewer than 2 graded assignments
+}
+
+#[wasm_bindgen]
+pub fn analyze_trajectory_clusters(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: TrajectoryClusteringOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = cluster_trajectories(&grades, &assignments, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Each student's percentages on the course timeline, with missing work
+// interpolated from its neighbours, plus how many assignments were graded
+fn timeline_rows(grades: &[Grade], assignments: &[Assignment]) -> BTreeMap<String, (usize, Vec<f64>)> {
+    // Group grades by student (sorted for deterministic output)
+    let mut student_grades: BTreeMap<String, Vec<&Grade>> = BTreeMap::new();
+    for grade in grades {
+        student_grades.entry(grade.student_id.clone()).or_default().push(grade);
+    }
+
+    let timeline = assignment_timeline(grades, assignments);
+    student_grades.into_iter()
+        .map(|(student_id, list)| {
+            let trajectory = timeline_trajectory(&list, &timeline);
+            let graded = trajectory.iter().flatten().count();
+            (student_id, (graded, fill_gaps(&trajectory)))
+        })
+        .collect()
+}
+
+// Linear interpolation across gaps; leading and trailing gaps take the nearest score
+fn fill_gaps(trajectory: &[Option<f64>]) -> Vec<f64> {
+    let known: Vec<(usize, f64)> = trajectory.iter()
+        .enumerate()
+        .filter_map(|(i, score)| score.map(|s| (i, s)))
+        .collect();
+    if known.is_empty() {
+        return vec![];
+    }
+    (0..trajectory.len())
+        .map(|i| {
+            let after = known.iter().position(|(j, _)| *j >= i);
+            match after {
+                Some(0) => known[0].1,
+                Some(n) => {
+                    let ((x0, y0), (x1, y1)) = (known[n - 1], known[n]);
+                    y0 + (y1 - y0) * (i - x0) as f64 / (x1 - x0) as f64
+                }
+                None => known[known.len() - 1].1,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn cluster_trajectories(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &TrajectoryClusteringOptions,
+) -> TrajectoryClusteringResult {
+    let rows = timeline_rows(grades, assignments);
+
+    let total_students = rows.len();
+    let mut student_ids: Vec<String> = Vec::new();
+    let mut raw: Vec<Vec<f64>> = Vec::new();
+    let mut unclustered_students: Vec<String> = Vec::new();
+
+    for (student_id, (graded, scores)) in rows {
+        if graded < 2 {
+            unclustered_students.push(student_id);
+        } else {
+            student_ids.push(student_id);
+            raw.push(scores);
+        }
+    }
+
+    let points = options.resample_points.max(2);
+    let resampled: Vec<Vec<f64>> = raw.iter()
+        .map(|scores| resample(scores, points))
+        .collect();
+
+    let k = options.clusters.max(1).min(resampled.len());
+    let assignments_by_student = if k == 0 {
+        vec![]
+    } else {
+        match options.method {
+            ClusteringMethod::Kmeans => kmeans(&resampled, k, options.max_iterations),
+            ClusteringMethod::Dtw => hierarchical_dtw(&raw, k),
+        }
+    };
+
+    // Collect members and centroids per cluster
+    let mut clusters: Vec<TrajectoryCluster> = Vec::new();
+    for cluster_id in 0..k {
+        let member_indices: Vec<usize> = assignments_by_student.iter()
+            .enumerate()
+            .filter(|(_, c)| **c == cluster_id)
+            .map(|(i, _)| i)
+            .collect();
+
+        if member_indices.is_empty() {
+            continue;
+        }
+
+        let centroid = mean_curve(&member_indices.iter().map(|i| &resampled[*i]).collect::<Vec<_>>());
+        let (archetype, description) = label_archetype(&centroid);
+
+        clusters.push(TrajectoryCluster {
+            cluster_id: clusters.len(),
+            archetype,
+            description,
+            size: member_indices.len(),
+            members: member_indices.iter().map(|i| student_ids[*i].clone()).collect(),
+            centroid,
+        });
+    }
+
+    // Largest groups first
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.size));
+    for (i, cluster) in clusters.iter_mut().enumerate() {
+        cluster.cluster_id = i;
+    }
+
+    TrajectoryClusteringResult {
+        method: match options.method {
+            ClusteringMethod::Kmeans => "kmeans".to_string(),
+            ClusteringMethod::Dtw => "dtw".to_string(),
+        },
+        clusters,
+        total_students,
+        unclustered_students,
+    }
+}
+
+// Linearly interpolate a series onto `points` evenly spaced positions
+pub(crate) fn resample(scores: &[f64], points: usize) -> Vec<f64> {
+    if scores.is_empty() {
+        return vec![0.0; points];
+    }
+    if scores.len() == 1 || points < 2 {
+        return vec![scores[0]; points];
+    }
+
+    let last = (scores.len() - 1) as f64;
+    (0..points)
+        .map(|i| {
+            let position = i as f64 * last / (points - 1) as f64;
+            let lower = position.floor() as usize;
+            let upper = (lower + 1).min(scores.len() - 1);
+            let fraction = position - lower as f64;
+            scores[lower] + (scores[upper] - scores[lower]) * fraction
+        })
+        .collect()
+}
+
+fn mean_curve(curves: &[&Vec<f64>]) -> Vec<f64> {
+    if curves.is_empty() {
+        return vec![];
+    }
+    (0..curves[0].len())
+        .map(|i| curves.iter().map(|c| c[i]).sum::<f64>() / curves.len() as f64)
+        .collect()
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum()
+}
+
+// k-means with deterministic farthest-first initialisation
+fn kmeans(data: &[Vec<f64>], k: usize, max_iterations: usize) -> Vec<usize> {
+    // Seed with the best-performing trajectory, then repeatedly the point
+    // furthest from every chosen centroid
+    let first = data.iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| calculate_mean(a).partial_cmp(&calculate_mean(b)).unwrap())
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let mut centroids: Vec<Vec<f64>> = vec![data[first].clone()];
+
+    while centroids.len() < k {
+        let next = data.iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let nearest = centroids.iter()
+                    .map(|c| squared_distance(point, c))
+                    .fold(f64::INFINITY, f64::min);
+                (i, nearest)
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        centroids.push(data[next].clone());
+    }
+
+    let mut labels = vec![0usize; data.len()];
+    for _ in 0..max_iterations.max(1) {
+        // Assignment step
+        let mut changed = false;
+        for (i, point) in data.iter().enumerate() {
+            let nearest = centroids.iter()
+                .enumerate()
+                .map(|(c, centroid)| (c, squared_distance(point, centroid)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .map(|(c, _)| c)
+                .unwrap_or(0);
+            if labels[i] != nearest {
+                labels[i] = nearest;
+                changed = true;
+            }
+        }
+
+        // Update step (empty clusters keep their previous centroid)
+        for (c, centroid) in centroids.iter_mut().enumerate() {
+            let members: Vec<&Vec<f64>> = data.iter()
+                .zip(labels.iter())
+                .filter(|(_, label)| **label == c)
+                .map(|(point, _)| point)
+                .collect();
+            if !members.is_empty() {
+                *centroid = mean_curve(&members);
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    labels
+}
+
+// Dynamic time warping distance between two series of possibly different lengths
+pub(crate) fn dtw_distance(a: &[f64], b: &[f64]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return f64::INFINITY;
+    }
+
+    let mut cost = vec![vec![f64::INFINITY; b.len() + 1]; a.len() + 1];
+    cost[0][0] = 0.0;
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let d = (a[i - 1] - b[j - 1]).abs();
+            cost[i][j] = d + cost[i - 1][j].min(cost[i][j - 1]).min(cost[i - 1][j - 1]);
+        }
+    }
+
+    // Normalise by path length bound so long and short series are comparable
+    cost[a.len()][b.len()] / (a.len() + b.len()) as f64
+}
+
+// Average-linkage agglomerative clustering on DTW distances
+fn hierarchical_dtw(data: &[Vec<f64>], k: usize) -> Vec<usize> {
+    let n = data.len();
+    let mut distances = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let d = dtw_distance(&data[i], &data[j]);
+            distances[i][j] = d;
+            distances[j][i] = d;
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+
+    while groups.len() > k {
+        let mut best = (0, 1, f64::INFINITY);
+        for a in 0..groups.len() {
+            for b in (a + 1)..groups.len() {
+                let total: f64 = groups[a].iter()
+                    .flat_map(|i| groups[b].iter().map(move |j| (*i, *j)))
+                    .map(|(i, j)| distances[i][j])
+                    .sum();
+                let linkage = total / (groups[a].len() * groups[b].len()) as f64;
+                if linkage < best.2 {
+                    best = (a, b, linkage);
+                }
+            }
+        }
+
+        let merged = groups.remove(best.1);
+        groups[best.0].extend(merged);
+    }
+
+    let mut labels = vec![0usize; n];
+    for (c, group) in groups.iter().enumerate() {
+        for i in group {
+            labels[*i] = c;
+        }
+    }
+    labels
+}
+
+// Name a centroid curve by its level and shape
+fn label_archetype(centroid: &[f64]) -> (String, String) {
+    let third = (centroid.len() / 3).max(1);
+    let start = calculate_mean(&centroid[..third]);
+    let end = calculate_mean(&centroid[centroid.len() - third..]);
+    let middle = centroid[centroid.len() / 2];
+    let overall = calculate_mean(centroid);
+    let change = end - start;
+
+    if change >= 10.0 {
+        // Most of the gain arriving in the second half marks a late bloomer
+        if end - middle > middle - start {
+            ("late_bloomer".to_string(), format!("Starts near {:.0}% and climbs late in the term to {:.0}%", start, end))
+        } else {
+            ("early_riser".to_string(), format!("Improves early from {:.0}% and holds around {:.0}%", start, end))
+        }
+    } else if change <= -10.0 {
+        if start - middle > middle - end {
+            ("early_fade".to_string(), format!("Drops early from {:.0}% and settles near {:.0}%", start, end))
+        } else {
+            ("late_fade".to_string(), format!("Holds near {:.0}% then falls off late to {:.0}%", start, end))
+        }
+    } else if overall >= 80.0 {
+        ("steady_high".to_string(), format!("Consistently strong, around {:.0}%", overall))
+    } else if overall < 65.0 {
+        ("steady_low".to_string(), format!("Consistently struggling, around {:.0}%", overall))
+    } else {
+        ("steady_middle".to_string(), format!("Stable mid-range performance, around {:.0}%", overall))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grades_for(student_id: &str, scores: &[f64]) -> Vec<Grade> {
+        scores.iter()
+            .enumerate()
+            .map(|(i, score)| Grade {
+                student_id: student_id.to_string(),
+                assignment_id: format!("A{}", i + 1),
+                score: *score,
+                max_score: 100.0,
+                submitted_at: None,
+                due_date: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_and_dtw() {
+        assert_eq!(resample(&[0.0, 100.0], 3), vec![0.0, 50.0, 100.0]);
+        assert_eq!(dtw_distance(&[50.0, 60.0], &[50.0, 50.0, 60.0]), 0.0);
+    }
+
+    #[test]
+    fn test_trajectory_archetypes() {
+        let mut grades = Vec::new();
+        grades.extend(grades_for("S1", &[90.0, 92.0, 91.0, 93.0, 92.0, 94.0]));
+        grades.extend(grades_for("S2", &[91.0, 90.0, 93.0, 92.0, 94.0, 93.0]));
+        grades.extend(grades_for("S3", &[50.0, 52.0, 51.0, 55.0, 80.0, 90.0]));
+        grades.extend(grades_for("S4", &[48.0, 50.0, 53.0, 56.0, 82.0, 88.0]));
+        grades.extend(grades_for("S5", &[40.0]));
+
+        for method in [ClusteringMethod::Kmeans, ClusteringMethod::Dtw] {
+            let options = TrajectoryClusteringOptions {
+                method,
+                clusters: 2,
+                ..Default::default()
+            };
+            let result = cluster_trajectories(&grades, &[], &options);
+
+            assert_eq!(result.total_students, 5);
+            assert_eq!(result.unclustered_students, vec!["S5".to_string()]);
+            assert_eq!(result.clusters.len(), 2);
+
+            let archetypes: Vec<&str> = result.clusters.iter().map(|c| c.archetype.as_str()).collect();
+            assert!(archetypes.contains(&"steady_high"));
+            assert!(archetypes.contains(&"late_bloomer"));
+        }
+    }
+
+    #[test]
+    fn test_rows_follow_the_timeline() {
+        let mut grades = Vec::new();
+        grades.extend(grades_for("S1", &[90.0, 92.0, 91.0, 93.0]));
+        grades.extend(grades_for("S2", &[91.0, 90.0, 93.0, 92.0]));
+        grades.extend(grades_for("S3", &[50.0, 52.0, 85.0, 90.0]));
+        grades.extend(grades_for("S4", &[48.0, 55.0, 82.0, 88.0]));
+        grades.extend(grades_for("S5", &[60.0, 62.0, 0.0, 64.0]));
+        grades.extend(grades_for("S6", &[50.0, 0.0, 85.0, 90.0]));
+        // S5's third quiz was graded out of zero; S6 skipped the second
+        grades.iter_mut().find(|g| g.student_id == "S5" && g.assignment_id == "A3").unwrap().max_score = 0.0;
+        grades.retain(|g| !(g.student_id == "S6" && g.assignment_id == "A2"));
+
+        let rows = timeline_rows(&grades, &[]);
+        assert_eq!(rows["S5"], (3, vec![60.0, 62.0, 63.0, 64.0]));
+        assert_eq!(rows["S6"], (3, vec![50.0, 67.5, 85.0, 90.0]));
+
+        let options = TrajectoryClusteringOptions {
+            clusters: 3,
+            ..Default::default()
+        };
+        let result = cluster_trajectories(&grades, &[], &options);
+        assert!(result.unclustered_students.is_empty());
+        let bloomers = result.clusters.iter().find(|c| c.members.contains(&"S3".to_string())).unwrap();
+        assert!(bloomers.members.contains(&"S6".to_string()));
+    }
+}
@@ -0,0 +1,312 @@
+// ============================================================================
+// Comparative Performance Analysis (percentiles, z-scores, class benchmarks)
+// ============================================================================
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::topics::{TopicMapper, TopicMapping};
+use crate::{calculate_mean, calculate_quantile, calculate_std_deviation, Assignment, Grade};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ComparativeOptions {
+    pub privacy_mode: bool,        // Report quintile bands only, never exact ranks
+    pub min_cohort_size: usize,    // Privacy mode: students a scope needs before anything is reported
+    pub topics: TopicMapping,
+}
+
+impl Default for ComparativeOptions {
+    fn default() -> Self {
+        ComparativeOptions {
+            privacy_mode: false,
+            min_cohort_size: 10,
+            topics: TopicMapping::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ComparativeMetric {
+    pub scope: String,                     // "overall", "assignment", "chapter"
+    pub scope_id: String,
+    pub scope_name: String,
+    pub score: f64,                        // Student's own percentage
+    pub percentile_rank: Option<f64>,      // 0-100; None in privacy mode
+    pub z_score: Option<f64>,              // None in privacy mode
+    pub distance_from_median: Option<f64>, // Percentage points; None in privacy mode
+    pub quintile_band: Option<String>,     // "top_quintile" ... "bottom_quintile"; None below min_cohort_size in privacy mode
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClassBenchmark {
+    pub scope: String,
+    pub scope_id: String,
+    pub scope_name: String,
+    pub mean: f64,
+    pub median: f64,
+    pub std_deviation: f64,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentComparison {
+    pub student_id: String,
+    pub overall: Option<ComparativeMetric>,
+    pub assignments: Vec<ComparativeMetric>,
+    pub chapters: Vec<ComparativeMetric>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ComparativePerformanceResult {
+    pub students: Vec<StudentComparison>,
+    pub benchmarks: Vec<ClassBenchmark>,   // Privacy mode: whole points, small cohorts omitted
+    pub privacy_mode: bool,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_comparative_performance(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: ComparativeOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let topics = options.topics.compile()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let result = compute_comparative_performance(&grades, &assignments, &options, &topics);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// One comparison scope: every student's score on it
+struct Scope {
+    kind: &'static str,
+    id: String,
+    name: String,
+    scores: BTreeMap<String, f64>,
+}
+
+pub(crate) fn compute_comparative_performance(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &ComparativeOptions,
+    topics: &TopicMapper,
+) -> ComparativePerformanceResult {
+    // Per-student percentage lists for each scope
+    let mut overall: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    let mut by_assignment: BTreeMap<String, BTreeMap<String, Vec<f64>>> = BTreeMap::new();
+    let mut by_chapter: BTreeMap<String, BTreeMap<String, Vec<f64>>> = BTreeMap::new();
+
+    for grade in grades.iter().filter(|g| g.max_score > 0.0) {
+        let percentage = (grade.score / grade.max_score) * 100.0;
+        overall.entry(grade.student_id.clone()).or_default().push(percentage);
+        by_assignment
+            .entry(grade.assignment_id.clone())
+            .or_default()
+            .entry(grade.student_id.clone())
+            .or_default()
+            .push(percentage);
+
+        if let Some(assignment) = assignments.iter().find(|a| a.id == grade.assignment_id) {
+            for chapter in topics.topics_or_other(assignment) {
+                by_chapter
+                    .entry(chapter)
+                    .or_default()
+                    .entry(grade.student_id.clone())
+                    .or_default()
+                    .push(percentage);
+            }
+        }
+    }
+
+    let average = |per_student: BTreeMap<String, Vec<f64>>| -> BTreeMap<String, f64> {
+        per_student.into_iter()
+            .map(|(student_id, scores)| (student_id, calculate_mean(&scores)))
+            .collect()
+    };
+
+    let mut scopes: Vec<Scope> = vec![Scope {
+        kind: "overall",
+        id: "overall".to_string(),
+        name: "Overall".to_string(),
+        scores: average(overall),
+    }];
+    for (assignment_id, per_student) in by_assignment {
+        let name = assignments.iter()
+            .find(|a| a.id == assignment_id)
+            .map(|a| a.name.clone())
+            .unwrap_or_else(|| assignment_id.clone());
+        scopes.push(Scope { kind: "assignment", id: assignment_id, name, scores: average(per_student) });
+    }
+    for (chapter, per_student) in by_chapter {
+        scopes.push(Scope { kind: "chapter", id: chapter.clone(), name: chapter, scores: average(per_student) });
+    }
+
+    // Class benchmarks and per-student positions within each scope
+    let mut benchmarks: Vec<ClassBenchmark> = Vec::new();
+    let mut students: BTreeMap<String, StudentComparison> = BTreeMap::new();
+
+    for scope in &scopes {
+        let mut sorted: Vec<f64> = scope.scores.values().copied().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mean = calculate_mean(&sorted);
+        let std_deviation = calculate_std_deviation(&sorted, mean);
+        let median = calculate_quantile(&sorted, 0.5);
+
+        // Small cohorts are too easy to re-identify, even by band
+        let reportable = !options.privacy_mode || sorted.len() >= options.min_cohort_size;
+        if reportable {
+            let shown = |value: f64| if options.privacy_mode { value.round() } else { value };
+            benchmarks.push(ClassBenchmark {
+                scope: scope.kind.to_string(),
+                scope_id: scope.id.clone(),
+                scope_name: scope.name.clone(),
+                mean: shown(mean),
+                median: shown(median),
+                std_deviation: shown(std_deviation),
+                count: sorted.len(),
+            });
+        }
+
+        for (student_id, score) in &scope.scores {
+            let percentile = percentile_rank(&sorted, *score);
+            let metric = ComparativeMetric {
+                scope: scope.kind.to_string(),
+                scope_id: scope.id.clone(),
+                scope_name: scope.name.clone(),
+                score: *score,
+                percentile_rank: (!options.privacy_mode).then_some(percentile),
+                z_score: (!options.privacy_mode).then_some(if std_deviation > 0.0 {
+                    (score - mean) / std_deviation
+                } else {
+                    0.0
+                }),
+                distance_from_median: (!options.privacy_mode).then_some(score - median),
+                quintile_band: reportable.then(|| quintile_band(percentile).to_string()),
+            };
+
+            let entry = students.entry(student_id.clone()).or_insert_with(|| StudentComparison {
+                student_id: student_id.clone(),
+                overall: None,
+                assignments: vec![],
+                chapters: vec![],
+            });
+            match scope.kind {
+                "overall" => entry.overall = Some(metric),
+                "assignment" => entry.assignments.push(metric),
+                _ => entry.chapters.push(metric),
+            }
+        }
+    }
+
+    ComparativePerformanceResult {
+        total_students: students.len(),
+        students: students.into_values().collect(),
+        benchmarks,
+        privacy_mode: options.privacy_mode,
+    }
+}
+
+// Percentile rank (0-100) with ties counted as half below
+pub(crate) fn percentile_rank(sorted: &[f64], score: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let below = sorted.iter().filter(|s| **s < score).count() as f64;
+    let equal = sorted.iter().filter(|s| **s == score).count() as f64;
+    (below + 0.5 * equal) / sorted.len() as f64 * 100.0
+}
+
+pub(crate) fn quintile_band(percentile: f64) -> &'static str {
+    if percentile >= 80.0 {
+        "top_quintile"
+    } else if percentile >= 60.0 {
+        "fourth_quintile"
+    } else if percentile >= 40.0 {
+        "middle_quintile"
+    } else if percentile >= 20.0 {
+        "second_quintile"
+    } else {
+        "bottom_quintile"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topics::default_mapper;
+
+    fn grade(student_id: &str, assignment_id: &str, score: f64) -> Grade {
+        Grade {
+            student_id: student_id.to_string(),
+            assignment_id: assignment_id.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        }
+    }
+
+    #[test]
+    fn test_comparative_positions_and_privacy_mode() {
+        let assignments = vec![Assignment {
+            id: "A1".to_string(),
+            name: "Chapter 1 Quiz".to_string(),
+            max_score: 100.0,
+            due_date: None,
+        }];
+        let grades: Vec<Grade> = [50.0, 60.0, 70.0, 80.0, 90.0].iter()
+            .enumerate()
+            .map(|(i, score)| grade(&format!("S{}", i + 1), "A1", *score))
+            .collect();
+
+        let open = compute_comparative_performance(&grades, &assignments, &ComparativeOptions::default(), default_mapper());
+        let top = open.students.iter().find(|s| s.student_id == "S5").unwrap();
+        let overall = top.overall.as_ref().unwrap();
+
+        assert_eq!(overall.percentile_rank, Some(90.0));
+        assert_eq!(overall.distance_from_median, Some(20.0));
+        assert!(overall.z_score.unwrap() > 1.0);
+        assert_eq!(overall.quintile_band.as_deref(), Some("top_quintile"));
+        assert_eq!(top.chapters[0].scope_id, "Chapter 1");
+
+        let private = compute_comparative_performance(
+            &grades,
+            &assignments,
+            &ComparativeOptions { privacy_mode: true, min_cohort_size: 5, ..ComparativeOptions::default() },
+            default_mapper(),
+        );
+        let bottom = private.students.iter().find(|s| s.student_id == "S1").unwrap();
+        let overall = bottom.overall.as_ref().unwrap();
+
+        assert!(overall.percentile_rank.is_none());
+        assert!(overall.z_score.is_none());
+        assert!(overall.distance_from_median.is_none());
+        assert_eq!(overall.quintile_band.as_deref(), Some("bottom_quintile"));
+        assert_eq!(private.benchmarks[0].std_deviation, private.benchmarks[0].std_deviation.round());
+
+        // Five students is below the default cohort minimum
+        let small = compute_comparative_performance(
+            &grades,
+            &assignments,
+            &ComparativeOptions { privacy_mode: true, ..ComparativeOptions::default() },
+            default_mapper(),
+        );
+        assert!(small.benchmarks.is_empty());
+        assert!(small.students.iter().all(|s| s.overall.as_ref().unwrap().quintile_band.is_none()));
+    }
+}
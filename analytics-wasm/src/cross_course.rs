@@ -0,0 +1,264 @@
+// ============================================================================
+// Cross-Course Performance Trends (several gradebooks, matched students)
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    calculate_correlation, compute_early_intervention, compute_learning_progression, Assignment,
+    Grade, ProgressionOptions,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CourseGradebook {
+    pub course_id: String,
+    #[serde(default)]
+    pub course_name: Option<String>,
+    pub grades: Vec<Grade>,
+    pub assignments: Vec<Assignment>,
+}
+
+// Maps a course-local student id onto the shared identity
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IdentityLink {
+    pub student_id: String,          // Canonical id used in the results
+    pub course_id: String,
+    pub course_student_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct CrossCourseInput {
+    pub courses: Vec<CourseGradebook>,
+    pub identities: Vec<IdentityLink>,   // Unlinked ids are assumed to match across courses
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CrossCourseOptions {
+    pub struggle_threshold: f64,     // Course average (0-100) below which a student is struggling
+    pub min_shared_students: usize,  // Students needed to report a course correlation
+}
+
+impl Default for CrossCourseOptions {
+    fn default() -> Self {
+        CrossCourseOptions {
+            struggle_threshold: 70.0,
+            min_shared_students: 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CoursePerformance {
+    pub course_id: String,
+    pub course_name: String,
+    pub average: f64,
+    pub risk_level: String,
+    pub trend: String,
+    pub struggling: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentCrossCourse {
+    pub student_id: String,
+    pub courses: Vec<CoursePerformance>,
+    pub struggling_courses: Vec<String>,
+    pub pattern: String,             // "struggling_everywhere", "course_specific", "on_track", "single_course"
+    pub overall_average: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CourseCorrelation {
+    pub course_a: String,
+    pub course_b: String,
+    pub shared_students: usize,
+    pub correlation: Option<f64>,    // None below min_shared_students
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CrossCourseResult {
+    pub students: Vec<StudentCrossCourse>,
+    pub correlations: Vec<CourseCorrelation>,
+    pub struggling_everywhere: Vec<String>,
+    pub total_courses: usize,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_cross_course_trends(courses_json: &str, options_json: &str) -> Result<String, JsValue> {
+    // Parse input data
+    let input: CrossCourseInput = serde_json::from_str(courses_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse courses: {}", e)))?;
+
+    let options: CrossCourseOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = compute_cross_course_trends(&input, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+pub(crate) fn compute_cross_course_trends(
+    input: &CrossCourseInput,
+    options: &CrossCourseOptions,
+) -> CrossCourseResult {
+    let canonical_id = |course_id: &str, student_id: &str| -> String {
+        input.identities.iter()
+            .find(|link| link.course_id == course_id && link.course_student_id == student_id)
+            .map(|link| link.student_id.clone())
+            .unwrap_or_else(|| student_id.to_string())
+    };
+
+    // Run the single-course insights per course, keyed by canonical student id
+    let mut per_student: BTreeMap<String, Vec<CoursePerformance>> = BTreeMap::new();
+    for course in &input.courses {
+        let risk = compute_early_intervention(&course.grades, &course.assignments, None);
+        let progression = compute_learning_progression(
+            &course.grades,
+            &course.assignments,
+            &ProgressionOptions::default(),
+        );
+        let course_name = course.course_name.clone().unwrap_or_else(|| course.course_id.clone());
+
+        for assessment in risk.high_risk.iter().chain(&risk.medium_risk).chain(&risk.low_risk) {
+            if assessment.grade_count == 0 {
+                continue;
+            }
+            let trend = progression.student_progressions.iter()
+                .find(|p| p.student_id == assessment.student_id)
+                .map(|p| p.metrics.overall_trend.clone())
+                .unwrap_or_else(|| "stable".to_string());
+            let struggling = assessment.raw_average < options.struggle_threshold
+                || assessment.risk_level == "high";
+
+            per_student
+                .entry(canonical_id(&course.course_id, &assessment.student_id))
+                .or_default()
+                .push(CoursePerformance {
+                    course_id: course.course_id.clone(),
+                    course_name: course_name.clone(),
+                    average: assessment.raw_average,
+                    risk_level: assessment.risk_level.clone(),
+                    trend,
+                    struggling,
+                });
+        }
+    }
+
+    let mut struggling_everywhere = Vec::new();
+    let students: Vec<StudentCrossCourse> = per_student.into_iter()
+        .map(|(student_id, mut courses)| {
+            courses.sort_by(|a, b| a.course_id.cmp(&b.course_id));
+            let struggling_courses: Vec<String> = courses.iter()
+                .filter(|c| c.struggling)
+                .map(|c| c.course_id.clone())
+                .collect();
+
+            let pattern = if courses.len() < 2 {
+                "single_course"
+            } else if struggling_courses.len() == courses.len() {
+                struggling_everywhere.push(student_id.clone());
+                "struggling_everywhere"
+            } else if !struggling_courses.is_empty() {
+                "course_specific"
+            } else {
+                "on_track"
+            };
+
+            StudentCrossCourse {
+                overall_average: courses.iter().map(|c| c.average).sum::<f64>() / courses.len() as f64,
+                student_id,
+                courses,
+                struggling_courses,
+                pattern: pattern.to_string(),
+            }
+        })
+        .collect();
+
+    // Pairwise correlations of course averages over shared students
+    let course_ids: Vec<String> = input.courses.iter()
+        .map(|c| c.course_id.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let mut correlations = Vec::new();
+    for (i, course_a) in course_ids.iter().enumerate() {
+        for course_b in &course_ids[i + 1..] {
+            let (xs, ys): (Vec<f64>, Vec<f64>) = students.iter()
+                .filter_map(|student| {
+                    let a = student.courses.iter().find(|c| c.course_id == *course_a)?;
+                    let b = student.courses.iter().find(|c| c.course_id == *course_b)?;
+                    Some((a.average, b.average))
+                })
+                .unzip();
+
+            correlations.push(CourseCorrelation {
+                course_a: course_a.clone(),
+                course_b: course_b.clone(),
+                shared_students: xs.len(),
+                correlation: (xs.len() >= options.min_shared_students.max(3))
+                    .then(|| calculate_correlation(&xs, &ys)),
+            });
+        }
+    }
+
+    CrossCourseResult {
+        total_courses: course_ids.len(),
+        total_students: students.len(),
+        students,
+        correlations,
+        struggling_everywhere,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradebook(course_id: &str, rows: &[(&str, f64)]) -> CourseGradebook {
+        CourseGradebook {
+            course_id: course_id.to_string(),
+            course_name: None,
+            grades: rows.iter()
+                .map(|(student, score)| Grade {
+                    student_id: student.to_string(),
+                    assignment_id: "A1".to_string(),
+                    score: *score,
+                    max_score: 100.0,
+                    submitted_at: None,
+                    due_date: None,
+                })
+                .collect(),
+            assignments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_cross_course_patterns_and_correlation() {
+        let calculus = gradebook("MATH101", &[("S1", 55.0), ("S2", 90.0), ("S3", 85.0), ("S4", 65.0), ("S5", 75.0)]);
+        // Physics uses its own ids for two students
+        let physics = gradebook("PHYS101", &[("p-1", 50.0), ("S2", 92.0), ("S3", 60.0), ("p-4", 62.0), ("S5", 78.0)]);
+        let input = CrossCourseInput {
+            courses: vec![calculus, physics],
+            identities: vec![
+                IdentityLink { student_id: "S1".to_string(), course_id: "PHYS101".to_string(), course_student_id: "p-1".to_string() },
+                IdentityLink { student_id: "S4".to_string(), course_id: "PHYS101".to_string(), course_student_id: "p-4".to_string() },
+            ],
+        };
+
+        let result = compute_cross_course_trends(&input, &CrossCourseOptions::default());
+
+        assert_eq!(result.total_students, 5);
+        assert_eq!(result.struggling_everywhere, vec!["S1".to_string(), "S4".to_string()]);
+        let s3 = result.students.iter().find(|s| s.student_id == "S3").unwrap();
+        assert_eq!(s3.pattern, "course_specific");
+        assert_eq!(s3.struggling_courses, vec!["PHYS101".to_string()]);
+        assert_eq!(result.correlations[0].shared_students, 5);
+        assert!(result.correlations[0].correlation.unwrap() > 0.5);
+    }
+}
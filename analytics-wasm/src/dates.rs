@@ -0,0 +1,134 @@
+// Minimal ISO 8601 parsing for gradebook timestamps ("2024-09-15",
+// "2024-09-15T23:59", "2024-09-15T23:59:00Z", "2024-09-15 23:59:00+05:30")
+
+use crate::{Assignment, Grade};
+
+// Parse a date or date-time into fractional days since 1970-01-01 UTC
+pub(crate) fn parse_timestamp(value: &str) -> Option<f64> {
+    let value = value.trim();
+    // get() rather than split_at(): byte 10 may fall inside a multi-byte char
+    let date_part = value.get(..10)?;
+    let rest = &value[date_part.len()..];
+    let mut fields = date_part.split('-');
+    let year: i64 = fields.next()?.parse().ok()?;
+    let month: u32 = fields.next()?.parse().ok()?;
+    let day: u32 = fields.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut days = days_from_civil(year, month, day) as f64;
+
+    let rest = rest.trim_start_matches(['T', 't', ' ']);
+    if rest.is_empty() {
+        return Some(days);
+    }
+
+    // Split off the timezone designator
+    let (time_part, offset_minutes) = if let Some(time) = rest.strip_suffix(['Z', 'z']) {
+        (time, 0.0)
+    } else if let Some(idx) = rest.rfind(['+', '-']) {
+        let (time, zone) = rest.split_at(idx);
+        let sign = if zone.starts_with('-') { -1.0 } else { 1.0 };
+        let zone = zone.get(1..)?.replace(':', "");
+        if zone.len() != 4 || !zone.is_ascii() {
+            return None;
+        }
+        let hours: f64 = zone[..2].parse().ok()?;
+        let minutes: f64 = zone[2..].parse().ok()?;
+        (time, sign * (hours * 60.0 + minutes))
+    } else {
+        (rest, 0.0)
+    };
+
+    let mut clock = time_part.split(':');
+    let hours: f64 = clock.next()?.parse().ok()?;
+    let minutes: f64 = clock.next().unwrap_or("0").parse().ok()?;
+    let seconds: f64 = clock.next().unwrap_or("0").parse().ok()?;
+
+    days += (hours * 3600.0 + minutes * 60.0 + seconds - offset_minutes * 60.0) / 86400.0;
+    Some(days)
+}
+
+// Assignment ids in course order with their due times (days since epoch).
+// Dated assignments are sorted by due date; undated ones follow in input
+// order (listed assignments first, then ids only seen in grades).
+pub(crate) fn assignment_timeline(grades: &[Grade], assignments: &[Assignment]) -> Vec<(String, Option<f64>)> {
+    let mut timeline: Vec<(String, Option<f64>)> = Vec::new();
+
+    let grade_due = |assignment_id: &str| -> Option<f64> {
+        grades.iter()
+            .filter(|g| g.assignment_id == assignment_id)
+            .find_map(|g| g.due_date.as_deref().and_then(parse_timestamp))
+    };
+
+    for assignment in assignments {
+        if timeline.iter().any(|(id, _)| *id == assignment.id) {
+            continue;
+        }
+        let due = assignment.due_date.as_deref()
+            .and_then(parse_timestamp)
+            .or_else(|| grade_due(&assignment.id));
+        timeline.push((assignment.id.clone(), due));
+    }
+
+    for grade in grades {
+        if !timeline.iter().any(|(id, _)| *id == grade.assignment_id) {
+            timeline.push((grade.assignment_id.clone(), grade_due(&grade.assignment_id)));
+        }
+    }
+
+    // Stable sort, so ties and undated assignments keep their input order
+    timeline.sort_by(|a, b| match (a.1, b.1) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap(),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+
+    timeline
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date (Hinnant's algorithm)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-02"), Some(1.0));
+        assert_eq!(parse_timestamp("2024-03-01T12:00:00Z"), Some(19783.5));
+        assert_eq!(parse_timestamp("2024-03-01T17:30:00+05:30"), Some(19783.5));
+        assert_eq!(parse_timestamp("2024-03-01 12:00"), Some(19783.5));
+        assert_eq!(parse_timestamp("not a date"), None);
+        assert_eq!(parse_timestamp("2024-10-0１"), None);
+        assert_eq!(parse_timestamp("2024-10-01T12:00+0１"), None);
+    }
+
+    #[test]
+    fn test_timeline_puts_undated_last() {
+        let assignment = |id: &str, due: Option<&str>| Assignment {
+            id: id.to_string(),
+            name: id.to_string(),
+            max_score: 10.0,
+            due_date: due.map(String::from),
+        };
+        let assignments = vec![
+            assignment("A1", None),
+            assignment("A2", Some("2024-09-20")),
+            assignment("A3", Some("2024-09-10")),
+        ];
+        let ids: Vec<String> = assignment_timeline(&[], &assignments).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["A3", "A2", "A1"]);
+    }
+}
@@ -0,0 +1,305 @@
+// ============================================================================
+// Grade Distribution Analysis
+// ============================================================================
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{calculate_mean, calculate_quantile, calculate_std_deviation, letter_grade, Assignment, Grade, LETTERS};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DistributionOptions {
+    pub bin_width: f64,          // Histogram bin width in percentage points (0.5-100)
+    pub quantiles: Vec<f64>,     // Quantiles to report (0-1)
+    pub floor_margin: f64,       // Scores at or below this percentage count toward the floor
+    pub ceiling_margin: f64,     // Scores at or above this percentage count toward the ceiling
+    pub effect_threshold: f64,   // Share of scores at floor/ceiling that flags an effect (0-1)
+}
+
+impl Default for DistributionOptions {
+    fn default() -> Self {
+        DistributionOptions {
+            bin_width: 10.0,
+            quantiles: vec![0.1, 0.25, 0.5, 0.75, 0.9],
+            floor_margin: 5.0,
+            ceiling_margin: 95.0,
+            effect_threshold: 0.15,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HistogramBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QuantileValue {
+    pub quantile: f64,
+    pub value: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LetterCount {
+    pub letter: String,
+    pub count: usize,
+    pub share: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DistributionSummary {
+    pub id: String,                   // Assignment id, or "running_total"
+    pub name: String,
+    pub count: usize,
+    pub mean: f64,
+    pub std_deviation: f64,
+    pub min: f64,
+    pub max: f64,
+    pub quantiles: Vec<QuantileValue>,
+    pub histogram: Vec<HistogramBin>,
+    pub skewness: f64,
+    pub kurtosis: f64,                // Excess kurtosis (0 for a normal curve)
+    pub bimodality_coefficient: f64,  // Above BIMODALITY_THRESHOLD suggests two peaks
+    pub likely_bimodal: bool,
+    pub floor_share: f64,
+    pub ceiling_share: f64,
+    pub floor_effect: bool,
+    pub ceiling_effect: bool,
+    pub letter_breakdown: Vec<LetterCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GradeDistributionResult {
+    pub assignments: Vec<DistributionSummary>,
+    pub running_total: DistributionSummary,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_grade_distribution(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: DistributionOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = compute_grade_distribution(&grades, &assignments, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+pub(crate) fn compute_grade_distribution(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &DistributionOptions,
+) -> GradeDistributionResult {
+    let assignment_summaries: Vec<DistributionSummary> = assignments.iter()
+        .map(|assignment| {
+            let scores: Vec<f64> = grades.iter()
+                .filter(|g| g.assignment_id == assignment.id && g.max_score > 0.0)
+                .map(|g| (g.score / g.max_score) * 100.0)
+                .collect();
+            summarize_distribution(&assignment.id, &assignment.name, &scores, options)
+        })
+        .collect();
+
+    // Running total: points earned over points possible on graded work
+    let mut totals: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+    for grade in grades.iter().filter(|g| g.max_score > 0.0) {
+        let entry = totals.entry(grade.student_id.as_str()).or_insert((0.0, 0.0));
+        entry.0 += grade.score;
+        entry.1 += grade.max_score;
+    }
+    let running_scores: Vec<f64> = totals.values()
+        .map(|(earned, possible)| earned / possible * 100.0)
+        .collect();
+
+    GradeDistributionResult {
+        assignments: assignment_summaries,
+        running_total: summarize_distribution("running_total", "Running Total", &running_scores, options),
+        total_students: totals.len(),
+    }
+}
+
+// Describe the shape of a set of percentage scores
+pub(crate) fn summarize_distribution(
+    id: &str,
+    name: &str,
+    scores: &[f64],
+    options: &DistributionOptions,
+) -> DistributionSummary {
+    let mut sorted = scores.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let count = sorted.len();
+    let mean = calculate_mean(&sorted);
+    let std_deviation = calculate_std_deviation(&sorted, mean);
+    let (skewness, kurtosis) = calculate_shape(&sorted, mean);
+    let bimodality_coefficient = calculate_bimodality_coefficient(skewness, kurtosis, count);
+
+    let share = |n: usize| if count > 0 { n as f64 / count as f64 } else { 0.0 };
+    let floor_share = share(sorted.iter().filter(|s| **s <= options.floor_margin).count());
+    let ceiling_share = share(sorted.iter().filter(|s| **s >= options.ceiling_margin).count());
+
+    let letter_breakdown = LETTERS.iter()
+        .map(|letter| {
+            let letter_count = sorted.iter().filter(|s| letter_grade(**s) == *letter).count();
+            LetterCount {
+                letter: letter.to_string(),
+                count: letter_count,
+                share: share(letter_count),
+            }
+        })
+        .collect();
+
+    DistributionSummary {
+        id: id.to_string(),
+        name: name.to_string(),
+        count,
+        mean,
+        std_deviation,
+        min: sorted.first().copied().unwrap_or(0.0),
+        max: sorted.last().copied().unwrap_or(0.0),
+        quantiles: options.quantiles.iter()
+            .map(|q| QuantileValue {
+                quantile: *q,
+                value: calculate_quantile(&sorted, *q),
+            })
+            .collect(),
+        histogram: build_histogram(&sorted, options.bin_width),
+        skewness,
+        kurtosis,
+        bimodality_coefficient,
+        likely_bimodal: bimodality_coefficient > BIMODALITY_THRESHOLD,
+        floor_share,
+        ceiling_share,
+        floor_effect: count > 0 && floor_share >= options.effect_threshold,
+        ceiling_effect: count > 0 && ceiling_share >= options.effect_threshold,
+        letter_breakdown,
+    }
+}
+
+// Fixed-width bins over 0-100; scores above 100 land in the last bin
+fn build_histogram(scores: &[f64], bin_width: f64) -> Vec<HistogramBin> {
+    // At most 200 bins, however small the requested width
+    let width = if bin_width > 0.0 { bin_width.clamp(0.5, 100.0) } else { 10.0 };
+    let bin_count = (100.0 / width).ceil() as usize;
+
+    let mut bins: Vec<HistogramBin> = (0..bin_count)
+        .map(|i| HistogramBin {
+            lower: i as f64 * width,
+            upper: ((i + 1) as f64 * width).min(100.0),
+            count: 0,
+        })
+        .collect();
+
+    for score in scores {
+        let index = ((score.max(0.0) / width).floor() as usize).min(bin_count - 1);
+        bins[index].count += 1;
+    }
+
+    bins
+}
+
+// Bias-corrected sample skewness and excess kurtosis
+pub(crate) fn calculate_shape(scores: &[f64], mean: f64) -> (f64, f64) {
+    let n = scores.len() as f64;
+    if scores.len() < 4 {
+        return (0.0, 0.0);
+    }
+
+    let m2 = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+    if m2 == 0.0 {
+        return (0.0, 0.0);
+    }
+    let m3 = scores.iter().map(|s| (s - mean).powi(3)).sum::<f64>() / n;
+    let m4 = scores.iter().map(|s| (s - mean).powi(4)).sum::<f64>() / n;
+
+    let g1 = m3 / m2.powf(1.5);
+    let g2 = m4 / (m2 * m2) - 3.0;
+
+    let skewness = g1 * (n * (n - 1.0)).sqrt() / (n - 2.0);
+    let kurtosis = ((n + 1.0) * g2 + 6.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0));
+
+    (skewness, kurtosis)
+}
+
+// Bimodality coefficient of a uniform distribution (5/9); higher suggests two peaks
+pub(crate) const BIMODALITY_THRESHOLD: f64 = 0.555;
+
+// Sarle's bimodality coefficient from sample skewness and excess kurtosis
+pub(crate) fn calculate_bimodality_coefficient(skewness: f64, kurtosis: f64, count: usize) -> f64 {
+    if count < 4 {
+        return 0.0;
+    }
+    let n = count as f64;
+    let correction = 3.0 * (n - 1.0).powi(2) / ((n - 2.0) * (n - 3.0));
+    let denominator = kurtosis + correction;
+    if denominator <= 0.0 {
+        return 0.0;
+    }
+    (skewness * skewness + 1.0) / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bimodal_assignment_is_flagged() {
+        let split = [
+            0.0, 2.0, 5.0, 12.0, 6.0, 8.0, 10.0, 4.0, 15.0, 9.0,
+            95.0, 98.0, 100.0, 92.0, 97.0, 90.0, 94.0, 99.0, 91.0, 96.0,
+        ];
+        let summary = summarize_distribution("A1", "Midterm", &split, &DistributionOptions::default());
+
+        assert_eq!(summary.count, 20);
+        assert!(summary.likely_bimodal);
+        assert!(summary.floor_effect);
+        assert!(summary.ceiling_effect);
+        assert_eq!(summary.histogram.len(), 10);
+        assert_eq!(summary.histogram.iter().map(|b| b.count).sum::<usize>(), 20);
+        assert_eq!(summary.letter_breakdown[0].count, 10);
+        assert_eq!(summary.letter_breakdown[4].count, 10);
+
+        // A tiny width would otherwise allocate millions of bins
+        assert_eq!(build_histogram(&split, 1e-9).len(), 200);
+    }
+
+    #[test]
+    fn test_running_total_uses_points() {
+        let grade = |student: &str, assignment: &str, score: f64, max_score: f64| Grade {
+            student_id: student.to_string(),
+            assignment_id: assignment.to_string(),
+            score,
+            max_score,
+            submitted_at: None,
+            due_date: None,
+        };
+        let grades = vec![
+            grade("S1", "A1", 10.0, 10.0),
+            grade("S1", "A2", 45.0, 90.0),
+            grade("S2", "A1", 5.0, 10.0),
+        ];
+
+        let result = compute_grade_distribution(&grades, &[], &DistributionOptions::default());
+
+        assert_eq!(result.total_students, 2);
+        assert!((result.running_total.min - 50.0).abs() < 1e-9);
+        assert!((result.running_total.max - 55.0).abs() < 1e-9);
+    }
+}
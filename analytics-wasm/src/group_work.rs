@@ -0,0 +1,391 @@
+// ============================================================================
+// Group Work Contribution (teams, peer evaluations, WebPA factors)
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{calculate_mean, Assignment, Grade};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Team {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub members: Vec<String>,
+    #[serde(default)]
+    pub assignments: Vec<String>,        // Group assignments done as this team; empty = all of them
+}
+
+impl Team {
+    // Whether this team did the group assignment together
+    fn worked_on(&self, assignment_id: &str) -> bool {
+        self.assignments.is_empty() || self.assignments.iter().any(|a| a == assignment_id)
+    }
+}
+
+// One rater's mark for one teammate (self-ratings allowed)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerEvaluation {
+    pub team_id: String,
+    pub rater_id: String,
+    pub ratee_id: String,
+    pub score: f64,
+    #[serde(default)]
+    pub assignment_id: Option<String>,   // Separate rounds per group assignment
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct GroupWorkModel {
+    pub teams: Vec<Team>,
+    pub group_assignments: Vec<String>,  // Assignment ids graded per team
+    pub peer_evaluations: Vec<PeerEvaluation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct GroupWorkOptions {
+    pub pa_weighting: f64,               // Share (0-1) of the team mark moderated by peers
+    pub include_self_assessment: bool,
+    pub free_rider_threshold: f64,       // Factor below this flags a free-rider
+    pub over_contributor_threshold: f64, // Factor above this flags an over-contributor
+}
+
+impl Default for GroupWorkOptions {
+    fn default() -> Self {
+        GroupWorkOptions {
+            pa_weighting: 0.5,
+            include_self_assessment: true,
+            free_rider_threshold: 0.8,
+            over_contributor_threshold: 1.2,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MemberContribution {
+    pub student_id: String,
+    pub contribution_factor: Option<f64>,   // WebPA factor; 1.0 = fair share
+    pub ratings_received: usize,
+    pub team_score: Option<f64>,
+    pub adjusted_score: Option<f64>,        // Team score moderated by the factor
+    pub individual_average: Option<f64>,    // Non-group assignments
+    pub individual_vs_team: Option<f64>,    // Individual average minus team score
+    pub flag: String,                       // "free_rider", "over_contributor", "balanced", "no_ratings"
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TeamContribution {
+    pub team_id: String,
+    pub team_name: String,
+    pub team_score: Option<f64>,
+    pub raters_responded: usize,
+    pub members: Vec<MemberContribution>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupWorkResult {
+    pub teams: Vec<TeamContribution>,
+    pub free_riders: Vec<String>,
+    pub over_contributors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub total_teams: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_group_work(
+    grades_json: &str,
+    assignments_json: &str,
+    teams_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let model: GroupWorkModel = serde_json::from_str(teams_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse teams: {}", e)))?;
+
+    let options: GroupWorkOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = compute_group_work(&grades, &assignments, &model, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Drop evaluations that do not fit the team model, explaining each
+pub(crate) fn validate_peer_evaluations<'a>(
+    model: &'a GroupWorkModel,
+    warnings: &mut Vec<String>,
+) -> Vec<&'a PeerEvaluation> {
+    model.peer_evaluations.iter()
+        .filter(|evaluation| {
+            let Some(team) = model.teams.iter().find(|t| t.id == evaluation.team_id) else {
+                warnings.push(format!("Peer evaluation references unknown team {}", evaluation.team_id));
+                return false;
+            };
+            for person in [&evaluation.rater_id, &evaluation.ratee_id] {
+                if !team.members.contains(person) {
+                    warnings.push(format!("{} is not a member of team {}", person, team.id));
+                    return false;
+                }
+            }
+            if let Some(assignment_id) = &evaluation.assignment_id
+                && !team.worked_on(assignment_id)
+            {
+                warnings.push(format!("Team {} did not work on {} together", team.id, assignment_id));
+                return false;
+            }
+            if !evaluation.score.is_finite() || evaluation.score < 0.0 {
+                warnings.push(format!(
+                    "Ignoring invalid rating {} from {} for {}",
+                    evaluation.score, evaluation.rater_id, evaluation.ratee_id
+                ));
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
+// WebPA: each rater's marks are normalised to sum to 1 and a member's factor is
+// the sum of fractions received over their fair share, so 1.0 = equal effort
+pub(crate) fn webpa_factors(
+    members: &[String],
+    evaluations: &[&PeerEvaluation],
+    include_self_assessment: bool,
+) -> (BTreeMap<String, f64>, usize) {
+    let mut by_rater: BTreeMap<&str, Vec<&PeerEvaluation>> = BTreeMap::new();
+    for evaluation in evaluations {
+        if include_self_assessment || evaluation.rater_id != evaluation.ratee_id {
+            by_rater.entry(evaluation.rater_id.as_str()).or_default().push(evaluation);
+        }
+    }
+    by_rater.retain(|_, ratings| ratings.iter().map(|r| r.score).sum::<f64>() > 0.0);
+
+    let eligible = |rater: &str, ratee: &str| include_self_assessment || rater != ratee;
+    let mut received: BTreeMap<&str, f64> = BTreeMap::new();
+    let mut fair_share: BTreeMap<&str, f64> = BTreeMap::new();
+
+    for (rater, ratings) in &by_rater {
+        let total: f64 = ratings.iter().map(|r| r.score).sum();
+        for rating in ratings {
+            *received.entry(rating.ratee_id.as_str()).or_default() += rating.score / total;
+        }
+        let ratees = members.iter().filter(|m| eligible(rater, m)).count();
+        for member in members.iter().filter(|m| eligible(rater, m)) {
+            *fair_share.entry(member.as_str()).or_default() += 1.0 / ratees as f64;
+        }
+    }
+
+    let factors = members.iter()
+        .filter_map(|member| {
+            let share = fair_share.get(member.as_str()).copied().unwrap_or(0.0);
+            (share > 0.0).then(|| {
+                let fractions = received.get(member.as_str()).copied().unwrap_or(0.0);
+                (member.clone(), fractions / share)
+            })
+        })
+        .collect();
+
+    (factors, by_rater.len())
+}
+
+pub(crate) fn compute_group_work(
+    grades: &[Grade],
+    _assignments: &[Assignment],
+    model: &GroupWorkModel,
+    options: &GroupWorkOptions,
+) -> GroupWorkResult {
+    let mut warnings: Vec<String> = Vec::new();
+    let evaluations = validate_peer_evaluations(model, &mut warnings);
+    let group_assignments: BTreeSet<&str> = model.group_assignments.iter().map(|s| s.as_str()).collect();
+
+    let percentage = |g: &Grade| (g.score / g.max_score) * 100.0;
+    let individual_average = |student_id: &str| -> Option<f64> {
+        let scores: Vec<f64> = grades.iter()
+            .filter(|g| g.student_id == student_id && g.max_score > 0.0)
+            .filter(|g| !group_assignments.contains(g.assignment_id.as_str()))
+            .map(percentage)
+            .collect();
+        (!scores.is_empty()).then(|| calculate_mean(&scores))
+    };
+
+    let mut free_riders = Vec::new();
+    let mut over_contributors = Vec::new();
+
+    let teams: Vec<TeamContribution> = model.teams.iter()
+        .map(|team| {
+            // Group grades are usually copied to every member; average them over
+            // the projects this team did, not ones members did in other teams
+            let team_grades: Vec<f64> = grades.iter()
+                .filter(|g| team.members.contains(&g.student_id) && g.max_score > 0.0)
+                .filter(|g| group_assignments.contains(g.assignment_id.as_str()) && team.worked_on(&g.assignment_id))
+                .map(percentage)
+                .collect();
+            let team_score = (!team_grades.is_empty()).then(|| calculate_mean(&team_grades));
+
+            // One WebPA round per group assignment; factors averaged over rounds
+            let mut rounds: BTreeMap<Option<&str>, Vec<&PeerEvaluation>> = BTreeMap::new();
+            for evaluation in evaluations.iter().filter(|e| e.team_id == team.id) {
+                rounds.entry(evaluation.assignment_id.as_deref()).or_default().push(evaluation);
+            }
+            let mut factor_lists: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+            let mut raters_responded = 0;
+            for round in rounds.values() {
+                let (factors, raters) = webpa_factors(&team.members, round, options.include_self_assessment);
+                raters_responded = raters_responded.max(raters);
+                for (member, factor) in factors {
+                    factor_lists.entry(member).or_default().push(factor);
+                }
+            }
+            if raters_responded > 0 && raters_responded < team.members.len() {
+                warnings.push(format!(
+                    "Team {}: only {} of {} members submitted peer ratings",
+                    team.id, raters_responded, team.members.len()
+                ));
+            }
+
+            let members: Vec<MemberContribution> = team.members.iter()
+                .map(|student_id| {
+                    let contribution_factor = factor_lists.get(student_id).map(|f| calculate_mean(f));
+                    let flag = match contribution_factor {
+                        None => "no_ratings",
+                        Some(f) if f < options.free_rider_threshold => "free_rider",
+                        Some(f) if f > options.over_contributor_threshold => "over_contributor",
+                        Some(_) => "balanced",
+                    };
+                    match flag {
+                        "free_rider" => free_riders.push(student_id.clone()),
+                        "over_contributor" => over_contributors.push(student_id.clone()),
+                        _ => {}
+                    }
+
+                    let individual = individual_average(student_id);
+                    MemberContribution {
+                        student_id: student_id.clone(),
+                        contribution_factor,
+                        ratings_received: evaluations.iter()
+                            .filter(|e| e.team_id == team.id && e.ratee_id == *student_id)
+                            .filter(|e| options.include_self_assessment || e.rater_id != e.ratee_id)
+                            .count(),
+                        team_score,
+                        adjusted_score: team_score.map(|score| {
+                            let factor = contribution_factor.unwrap_or(1.0);
+                            let weight = options.pa_weighting.clamp(0.0, 1.0);
+                            (score * (1.0 - weight) + score * weight * factor).min(100.0)
+                        }),
+                        individual_average: individual,
+                        individual_vs_team: individual.zip(team_score).map(|(own, team)| own - team),
+                        flag: flag.to_string(),
+                    }
+                })
+                .collect();
+
+            TeamContribution {
+                team_id: team.id.clone(),
+                team_name: team.name.clone().unwrap_or_else(|| team.id.clone()),
+                team_score,
+                raters_responded,
+                members,
+            }
+        })
+        .collect();
+
+    GroupWorkResult {
+        total_teams: teams.len(),
+        teams,
+        free_riders,
+        over_contributors,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webpa_factors_flag_free_rider() {
+        let grade = |student: &str, assignment: &str, score: f64| Grade {
+            student_id: student.to_string(),
+            assignment_id: assignment.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        };
+        let grades = vec![
+            grade("S1", "P1", 80.0), grade("S2", "P1", 80.0), grade("S3", "P1", 80.0),
+            grade("S1", "Q1", 90.0), grade("S2", "Q1", 85.0), grade("S3", "Q1", 50.0),
+        ];
+        let model: GroupWorkModel = serde_json::from_str(r#"{
+            "teams": [{"id": "T1", "members": ["S1", "S2", "S3"]}],
+            "group_assignments": ["P1"],
+            "peer_evaluations": [
+                {"team_id": "T1", "rater_id": "S1", "ratee_id": "S1", "score": 4},
+                {"team_id": "T1", "rater_id": "S1", "ratee_id": "S2", "score": 4},
+                {"team_id": "T1", "rater_id": "S1", "ratee_id": "S3", "score": 1},
+                {"team_id": "T1", "rater_id": "S2", "ratee_id": "S1", "score": 4},
+                {"team_id": "T1", "rater_id": "S2", "ratee_id": "S2", "score": 4},
+                {"team_id": "T1", "rater_id": "S2", "ratee_id": "S3", "score": 1},
+                {"team_id": "T1", "rater_id": "S3", "ratee_id": "S1", "score": 3},
+                {"team_id": "T1", "rater_id": "S3", "ratee_id": "S2", "score": 3},
+                {"team_id": "T1", "rater_id": "S3", "ratee_id": "S3", "score": 4},
+                {"team_id": "T1", "rater_id": "S4", "ratee_id": "S3", "score": 5}
+            ]
+        }"#).unwrap();
+
+        let result = compute_group_work(&grades, &[], &model, &GroupWorkOptions::default());
+        let team = &result.teams[0];
+        let factors: Vec<f64> = team.members.iter().map(|m| m.contribution_factor.unwrap()).collect();
+
+        // Fair share sums to the team size
+        assert!((factors.iter().sum::<f64>() - 3.0).abs() < 1e-9);
+        assert!((factors[0] - (8.0 / 9.0 + 0.3)).abs() < 1e-9);
+        assert_eq!(result.free_riders, vec!["S3".to_string()]);
+        assert_eq!(team.members[2].individual_vs_team, Some(-30.0));
+        assert!(team.members[0].adjusted_score.unwrap() > 80.0);
+        assert_eq!(result.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_team_score_covers_only_the_teams_projects() {
+        let grade = |student: &str, assignment: &str, score: f64| Grade {
+            student_id: student.to_string(),
+            assignment_id: assignment.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        };
+        let grades = vec![
+            grade("S1", "P1", 90.0), grade("S2", "P1", 90.0),
+            grade("S1", "P2", 50.0), grade("S3", "P2", 50.0),
+        ];
+        let model: GroupWorkModel = serde_json::from_str(r#"{
+            "teams": [
+                {"id": "T1", "members": ["S1", "S2"], "assignments": ["P1"]},
+                {"id": "T2", "members": ["S1", "S3"], "assignments": ["P2"]}
+            ],
+            "group_assignments": ["P1", "P2"],
+            "peer_evaluations": [
+                {"team_id": "T1", "rater_id": "S1", "ratee_id": "S2", "score": 3, "assignment_id": "P2"}
+            ]
+        }"#).unwrap();
+
+        let result = compute_group_work(&grades, &[], &model, &GroupWorkOptions::default());
+
+        assert_eq!(result.teams[0].team_score, Some(90.0));
+        assert_eq!(result.teams[1].team_score, Some(50.0));
+        assert_eq!(result.warnings, vec!["Team T1 did not work on P2 together".to_string()]);
+    }
+}
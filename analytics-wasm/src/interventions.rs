@@ -0,0 +1,283 @@
+// ============================================================================
+// Intervention Effectiveness (difference-in-differences vs matched peers)
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::{assignment_timeline, parse_timestamp};
+use crate::{calculate_mean, calculate_std_deviation, Assignment, Grade};
+
+// One outreach to a student
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Intervention {
+    pub student_id: String,
+    pub action: String,                       // "meeting", "tutoring_referral", "email", ...
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub applies_from_assignment: Option<String>,  // First assignment counted as "after"; beats date
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct InterventionOptions {
+    pub matches: usize,          // Non-intervened students matched per intervention
+    pub window: usize,           // Assignments either side of the cut-off; 0 = all
+    pub effect_margin: f64,      // Points of difference-in-differences that count as a change
+}
+
+impl Default for InterventionOptions {
+    fn default() -> Self {
+        InterventionOptions {
+            matches: 3,
+            window: 0,
+            effect_margin: 5.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InterventionEffect {
+    pub student_id: String,
+    pub action: String,
+    pub cutoff_assignment: Option<String>,
+    pub pre_mean: Option<f64>,
+    pub post_mean: Option<f64>,
+    pub change: Option<f64>,                  // Post minus pre for the student
+    pub control_students: Vec<String>,
+    pub control_change: Option<f64>,          // Mean post minus pre for the matched controls
+    pub did_estimate: Option<f64>,            // change - control_change
+    pub verdict: String,                      // "improved", "no_change", "worsened", "insufficient_data"
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ActionEffectiveness {
+    pub action: String,
+    pub interventions: usize,
+    pub evaluated: usize,
+    pub mean_effect: Option<f64>,
+    pub std_error: Option<f64>,
+    pub improved_share: f64,                  // 0-1 of evaluated
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InterventionEffectivenessResult {
+    pub interventions: Vec<InterventionEffect>,
+    pub by_action: Vec<ActionEffectiveness>,
+    pub overall_effect: Option<f64>,
+    pub total_interventions: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_intervention_effectiveness(
+    grades_json: &str,
+    assignments_json: &str,
+    interventions_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let interventions: Vec<Intervention> = serde_json::from_str(interventions_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse interventions: {}", e)))?;
+
+    let options: InterventionOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = compute_intervention_effectiveness(&grades, &assignments, &interventions, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Pre/post means of a series split at the cut-off, limited to the window
+fn pre_post(series: &[Option<f64>], cutoff: usize, window: usize) -> Option<(f64, f64)> {
+    let start = if window > 0 { cutoff.saturating_sub(window) } else { 0 };
+    let end = if window > 0 { (cutoff + window).min(series.len()) } else { series.len() };
+
+    let pre: Vec<f64> = series[start..cutoff].iter().flatten().copied().collect();
+    let post: Vec<f64> = series[cutoff..end].iter().flatten().copied().collect();
+    if pre.is_empty() || post.is_empty() {
+        return None;
+    }
+    Some((calculate_mean(&pre), calculate_mean(&post)))
+}
+
+pub(crate) fn compute_intervention_effectiveness(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    interventions: &[Intervention],
+    options: &InterventionOptions,
+) -> InterventionEffectivenessResult {
+    let timeline = assignment_timeline(grades, assignments);
+
+    // Per-student percentage at each timeline position
+    let mut series: BTreeMap<&str, Vec<Option<f64>>> = BTreeMap::new();
+    for student_id in grades.iter().map(|g| g.student_id.as_str()).collect::<BTreeSet<_>>() {
+        let row = timeline.iter()
+            .map(|(assignment_id, _)| {
+                let scores: Vec<f64> = grades.iter()
+                    .filter(|g| g.student_id == student_id && g.assignment_id == *assignment_id && g.max_score > 0.0)
+                    .map(|g| (g.score / g.max_score) * 100.0)
+                    .collect();
+                (!scores.is_empty()).then(|| calculate_mean(&scores))
+            })
+            .collect();
+        series.insert(student_id, row);
+    }
+
+    let intervened: BTreeSet<&str> = interventions.iter().map(|i| i.student_id.as_str()).collect();
+
+    let effects: Vec<InterventionEffect> = interventions.iter()
+        .map(|intervention| {
+            // First assignment the intervention could have influenced
+            let cutoff = match &intervention.applies_from_assignment {
+                Some(assignment_id) => timeline.iter().position(|(id, _)| id == assignment_id),
+                None => intervention.date.as_deref()
+                    .and_then(parse_timestamp)
+                    .and_then(|date| timeline.iter().position(|(_, due)| due.is_some_and(|d| d >= date))),
+            };
+
+            let mut effect = InterventionEffect {
+                student_id: intervention.student_id.clone(),
+                action: intervention.action.clone(),
+                cutoff_assignment: cutoff.map(|c| timeline[c].0.clone()),
+                pre_mean: None,
+                post_mean: None,
+                change: None,
+                control_students: vec![],
+                control_change: None,
+                did_estimate: None,
+                verdict: "insufficient_data".to_string(),
+            };
+
+            let Some(cutoff) = cutoff else {
+                return effect;
+            };
+            let Some((pre, post)) = series.get(intervention.student_id.as_str())
+                .and_then(|row| pre_post(row, cutoff, options.window))
+            else {
+                return effect;
+            };
+            effect.pre_mean = Some(pre);
+            effect.post_mean = Some(post);
+            effect.change = Some(post - pre);
+
+            // Nearest non-intervened students by pre-period mean
+            let mut candidates: Vec<(&str, f64, f64)> = series.iter()
+                .filter(|(id, _)| !intervened.contains(*id))
+                .filter_map(|(id, row)| pre_post(row, cutoff, options.window).map(|(p, q)| (*id, p, q)))
+                .collect();
+            candidates.sort_by(|a, b| (a.1 - pre).abs().partial_cmp(&(b.1 - pre).abs()).unwrap());
+            candidates.truncate(options.matches.max(1));
+            if candidates.is_empty() {
+                return effect;
+            }
+
+            let control_change = calculate_mean(&candidates.iter().map(|(_, p, q)| q - p).collect::<Vec<_>>());
+            let did = (post - pre) - control_change;
+            effect.control_students = candidates.iter().map(|(id, _, _)| id.to_string()).collect();
+            effect.control_change = Some(control_change);
+            effect.did_estimate = Some(did);
+            effect.verdict = if did >= options.effect_margin {
+                "improved"
+            } else if did <= -options.effect_margin {
+                "worsened"
+            } else {
+                "no_change"
+            }
+            .to_string();
+            effect
+        })
+        .collect();
+
+    let mut actions: BTreeMap<&str, Vec<&InterventionEffect>> = BTreeMap::new();
+    for effect in &effects {
+        actions.entry(effect.action.as_str()).or_default().push(effect);
+    }
+    let by_action: Vec<ActionEffectiveness> = actions.into_iter()
+        .map(|(action, list)| {
+            let estimates: Vec<f64> = list.iter().filter_map(|e| e.did_estimate).collect();
+            let mean = calculate_mean(&estimates);
+            ActionEffectiveness {
+                action: action.to_string(),
+                interventions: list.len(),
+                evaluated: estimates.len(),
+                mean_effect: (!estimates.is_empty()).then_some(mean),
+                std_error: (estimates.len() >= 2)
+                    .then(|| calculate_std_deviation(&estimates, mean) / (estimates.len() as f64).sqrt()),
+                improved_share: if estimates.is_empty() {
+                    0.0
+                } else {
+                    list.iter().filter(|e| e.verdict == "improved").count() as f64 / estimates.len() as f64
+                },
+            }
+        })
+        .collect();
+
+    let all_estimates: Vec<f64> = effects.iter().filter_map(|e| e.did_estimate).collect();
+
+    InterventionEffectivenessResult {
+        total_interventions: effects.len(),
+        overall_effect: (!all_estimates.is_empty()).then(|| calculate_mean(&all_estimates)),
+        interventions: effects,
+        by_action,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difference_in_differences_against_matched_peers() {
+        let assignments: Vec<Assignment> = (1..=4)
+            .map(|i| Assignment {
+                id: format!("A{}", i),
+                name: format!("Quiz {}", i),
+                max_score: 100.0,
+                due_date: Some(format!("2024-09-0{}", i * 2)),
+            })
+            .collect();
+        let rows = [
+            ("S1", [55.0, 50.0, 70.0, 75.0]),   // Met with the instructor before A3
+            ("S2", [54.0, 52.0, 57.0, 59.0]),   // Similar start, no outreach
+            ("S3", [90.0, 92.0, 91.0, 93.0]),
+        ];
+        let grades: Vec<Grade> = rows.iter()
+            .flat_map(|(student, scores)| {
+                scores.iter().enumerate().map(move |(i, score)| Grade {
+                    student_id: student.to_string(),
+                    assignment_id: format!("A{}", i + 1),
+                    score: *score,
+                    max_score: 100.0,
+                    submitted_at: None,
+                    due_date: None,
+                })
+            })
+            .collect();
+        let interventions: Vec<Intervention> = serde_json::from_str(r#"[
+            {"student_id": "S1", "action": "meeting", "date": "2024-09-05"}
+        ]"#).unwrap();
+        let options = InterventionOptions { matches: 1, ..Default::default() };
+
+        let result = compute_intervention_effectiveness(&grades, &assignments, &interventions, &options);
+        let effect = &result.interventions[0];
+
+        assert_eq!(effect.cutoff_assignment.as_deref(), Some("A3"));
+        assert_eq!(effect.control_students, vec!["S2".to_string()]);
+        assert!((effect.change.unwrap() - 20.0).abs() < 1e-9);
+        assert!((effect.did_estimate.unwrap() - 15.0).abs() < 1e-9);
+        assert_eq!(effect.verdict, "improved");
+        assert_eq!(result.by_action[0].evaluated, 1);
+    }
+}
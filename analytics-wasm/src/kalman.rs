@@ -0,0 +1,339 @@
+// ============================================================================
+// Latent Ability Tracking (Kalman filter + RTS smoother)
+// ============================================================================
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::assignment_timeline;
+use crate::shrinkage::{self, ShrinkagePrior};
+use crate::{
+    calculate_mean, calculate_recent_slope, momentum_label, trend_label, Assignment, Grade,
+    ProgressionMetrics,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct KalmanOptions {
+    pub process_variance: f64,             // Ability drift variance per week
+    pub observation_variance: Option<f64>, // Score noise; estimated from the data when absent
+    pub adjust_difficulty: bool,           // Centre each assignment on its class mean
+}
+
+impl Default for KalmanOptions {
+    fn default() -> Self {
+        KalmanOptions {
+            process_variance: 4.0,
+            observation_variance: None,
+            adjust_difficulty: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AbilityPoint {
+    pub assignment_id: String,
+    pub week: f64,                 // Weeks since the first assignment
+    pub observed: Option<f64>,     // Difficulty-adjusted score, None if missing
+    pub filtered_mean: f64,
+    pub filtered_std: f64,
+    pub smoothed_mean: f64,
+    pub smoothed_std: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AbilityTrajectory {
+    pub student_id: String,
+    pub points: Vec<AbilityPoint>,
+    pub current_ability: f64,      // Filtered estimate after the latest assignment
+    pub current_std: f64,
+    pub observations: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LatentAbilityResult {
+    pub students: Vec<AbilityTrajectory>,
+    pub observation_variance: f64,
+    pub process_variance: f64,
+    pub prior_mean: f64,
+    pub prior_variance: f64,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_latent_ability(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: KalmanOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = track_latent_ability(&grades, &assignments, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+pub(crate) fn track_latent_ability(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &KalmanOptions,
+) -> LatentAbilityResult {
+    // Time axis in weeks; undated courses fall back to one week per assignment
+    let timeline = assignment_timeline(grades, assignments);
+    let first_due = timeline.first().and_then(|(_, due)| *due);
+    let steps: Vec<(String, f64)> = timeline.iter()
+        .enumerate()
+        .map(|(i, (id, due))| {
+            let week = match (due, first_due) {
+                (Some(due), Some(first)) => (due - first) / 7.0,
+                _ => i as f64,
+            };
+            (id.clone(), week)
+        })
+        .collect();
+
+    // Item difficulty: each assignment's class mean relative to the grand mean
+    let mut assignment_scores: HashMap<&str, Vec<f64>> = HashMap::new();
+    for grade in grades.iter().filter(|g| g.max_score > 0.0) {
+        assignment_scores
+            .entry(grade.assignment_id.as_str())
+            .or_default()
+            .push((grade.score / grade.max_score) * 100.0);
+    }
+    let assignment_means: HashMap<&str, f64> = assignment_scores.iter()
+        .map(|(id, scores)| (*id, calculate_mean(scores)))
+        .collect();
+    let grand_mean = calculate_mean(&assignment_means.values().copied().collect::<Vec<_>>());
+
+    // Difficulty-adjusted observations per student (latest grade wins)
+    let mut observations: BTreeMap<String, HashMap<String, (f64, f64)>> = BTreeMap::new();
+    for grade in grades.iter().filter(|g| g.max_score > 0.0) {
+        let raw = (grade.score / grade.max_score) * 100.0;
+        let offset = if options.adjust_difficulty {
+            assignment_means.get(grade.assignment_id.as_str()).copied().unwrap_or(grand_mean) - grand_mean
+        } else {
+            0.0
+        };
+        observations
+            .entry(grade.student_id.clone())
+            .or_default()
+            .insert(grade.assignment_id.clone(), (raw - offset, raw));
+    }
+
+    // Noise and prior estimated from the class
+    let adjusted: Vec<Vec<f64>> = observations.values()
+        .map(|obs| obs.values().map(|(adjusted, _)| *adjusted).collect())
+        .collect();
+    let observation_variance = options.observation_variance
+        .unwrap_or_else(|| shrinkage::pooled_within_variance(&adjusted))
+        .max(1.0);
+    let prior_variance = ShrinkagePrior::for_averages(&adjusted)
+        .map(|prior| prior.between_variance)
+        .unwrap_or(0.0)
+        .max(25.0);
+    let process_variance = options.process_variance.max(0.0);
+
+    let students: Vec<AbilityTrajectory> = observations.iter()
+        .map(|(student_id, obs)| {
+            let series: Vec<(String, f64, Option<f64>)> = steps.iter()
+                .map(|(id, week)| (id.clone(), *week, obs.get(id).map(|(adjusted, _)| *adjusted)))
+                .collect();
+            let mut trajectory = filter_and_smooth(
+                &series,
+                grand_mean,
+                prior_variance,
+                process_variance,
+                observation_variance,
+            );
+            trajectory.student_id = student_id.clone();
+            trajectory
+        })
+        .collect();
+
+    LatentAbilityResult {
+        total_students: students.len(),
+        students,
+        observation_variance,
+        process_variance,
+        prior_mean: grand_mean,
+        prior_variance,
+    }
+}
+
+// Random-walk Kalman filter with a Rauch-Tung-Striebel backward pass.
+// Missing observations are predict-only steps.
+fn filter_and_smooth(
+    series: &[(String, f64, Option<f64>)],
+    prior_mean: f64,
+    prior_variance: f64,
+    process_variance: f64,
+    observation_variance: f64,
+) -> AbilityTrajectory {
+    let n = series.len();
+    let mut filtered_mean = vec![0.0; n];
+    let mut filtered_var = vec![0.0; n];
+    let mut predicted_var = vec![0.0; n];
+
+    let mut mean = prior_mean;
+    let mut variance = prior_variance;
+    let mut previous_week = series.first().map(|s| s.1).unwrap_or(0.0);
+
+    for (i, (_, week, observed)) in series.iter().enumerate() {
+        // Predict: ability drifts in proportion to elapsed time
+        let elapsed = (week - previous_week).max(0.0);
+        variance += process_variance * elapsed;
+        predicted_var[i] = variance;
+        previous_week = *week;
+
+        // Update
+        if let Some(y) = observed {
+            let gain = variance / (variance + observation_variance);
+            mean += gain * (y - mean);
+            variance *= 1.0 - gain;
+        }
+
+        filtered_mean[i] = mean;
+        filtered_var[i] = variance;
+    }
+
+    let mut smoothed_mean = filtered_mean.clone();
+    let mut smoothed_var = filtered_var.clone();
+    for i in (0..n.saturating_sub(1)).rev() {
+        let gain = if predicted_var[i + 1] > 0.0 {
+            filtered_var[i] / predicted_var[i + 1]
+        } else {
+            0.0
+        };
+        smoothed_mean[i] = filtered_mean[i] + gain * (smoothed_mean[i + 1] - filtered_mean[i]);
+        smoothed_var[i] = filtered_var[i] + gain * gain * (smoothed_var[i + 1] - predicted_var[i + 1]);
+    }
+
+    let points: Vec<AbilityPoint> = series.iter()
+        .enumerate()
+        .map(|(i, (id, week, observed))| AbilityPoint {
+            assignment_id: id.clone(),
+            week: *week,
+            observed: *observed,
+            filtered_mean: filtered_mean[i],
+            filtered_std: filtered_var[i].max(0.0).sqrt(),
+            smoothed_mean: smoothed_mean[i],
+            smoothed_std: smoothed_var[i].max(0.0).sqrt(),
+        })
+        .collect();
+
+    AbilityTrajectory {
+        student_id: String::new(),
+        current_ability: mean,
+        current_std: variance.sqrt(),
+        observations: series.iter().filter(|s| s.2.is_some()).count(),
+        points,
+    }
+}
+
+// Progression metrics derived from a smoothed ability trajectory
+pub(crate) fn progression_metrics(
+    trajectory: &AbilityTrajectory,
+    raw_scores: Vec<Option<f64>>,
+    prior_variance: f64,
+) -> ProgressionMetrics {
+    let smoothed: Vec<f64> = trajectory.points.iter().map(|p| p.smoothed_mean).collect();
+
+    if trajectory.observations < 2 {
+        return ProgressionMetrics {
+            overall_trend: "insufficient_data".to_string(),
+            trend_strength: 0.0,
+            velocity: 0.0,
+            shrunk_velocity: 0.0,
+            data_sufficiency: "insufficient".to_string(),
+            momentum: "unknown".to_string(),
+            current_performance: trajectory.current_ability,
+            projected_performance: trajectory.current_ability.clamp(0.0, 100.0),
+            raw_trajectory: raw_scores,
+            smoothed_trajectory: smoothed,
+        };
+    }
+
+    // The filter already regularises the slope, so raw and shrunk agree
+    let velocity = calculate_recent_slope(&smoothed, smoothed.len());
+    let reliability = if prior_variance > 0.0 {
+        (1.0 - trajectory.current_std.powi(2) / prior_variance).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    ProgressionMetrics {
+        overall_trend: trend_label(velocity),
+        trend_strength: (velocity.abs() / 10.0).min(1.0),
+        velocity,
+        shrunk_velocity: velocity,
+        data_sufficiency: shrinkage::data_sufficiency(trajectory.observations, reliability),
+        momentum: momentum_label(&smoothed),
+        current_performance: trajectory.current_ability,
+        projected_performance: (trajectory.current_ability + velocity).clamp(0.0, 100.0),
+        raw_trajectory: raw_scores,
+        smoothed_trajectory: smoothed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(student_id: &str, assignment_id: &str, score: f64) -> Grade {
+        Grade {
+            student_id: student_id.to_string(),
+            assignment_id: assignment_id.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        }
+    }
+
+    fn assignment(id: &str, due: &str) -> Assignment {
+        Assignment {
+            id: id.to_string(),
+            name: id.to_string(),
+            max_score: 100.0,
+            due_date: Some(due.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_missing_assignment_is_predict_only() {
+        let assignments = vec![
+            assignment("A1", "2024-09-02"),
+            assignment("A2", "2024-09-09"),
+            assignment("A3", "2024-09-30"),
+        ];
+        let grades = vec![
+            grade("S1", "A1", 60.0), grade("S1", "A3", 80.0),
+            grade("S2", "A1", 70.0), grade("S2", "A2", 72.0), grade("S2", "A3", 75.0),
+        ];
+
+        let result = track_latent_ability(&grades, &assignments, &KalmanOptions::default());
+        let s1 = result.students.iter().find(|s| s.student_id == "S1").unwrap();
+
+        assert_eq!(s1.points.len(), 3);
+        assert_eq!(s1.observations, 2);
+        assert!(s1.points[1].observed.is_none());
+        assert_eq!(s1.points[2].week, 4.0);
+        // Uncertainty grows over the gap and the smoother tightens it again
+        assert!(s1.points[1].filtered_std > s1.points[0].filtered_std);
+        assert!(s1.points[1].smoothed_std <= s1.points[1].filtered_std);
+        // The smoothed ability at the gap sits between the two observations
+        assert!(s1.points[1].smoothed_mean > s1.points[0].smoothed_mean);
+        assert!(s1.points[1].smoothed_mean < s1.points[2].smoothed_mean);
+    }
+}
@@ -0,0 +1,265 @@
+// ============================================================================
+// Knowledge Gap Identification (student x chapter mastery matrix)
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::topics::{TopicMapper, TopicMapping, UNMAPPED_TOPIC};
+use crate::{calculate_mean, calculate_std_deviation, Assignment, Grade};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct KnowledgeGapOptions {
+    pub own_margin: f64,          // Points below the student's own average
+    pub class_z_threshold: f64,   // Standard deviations below the class chapter mean
+    pub top_gaps: usize,          // Gaps reported per student
+    pub topics: TopicMapping,
+}
+
+impl Default for KnowledgeGapOptions {
+    fn default() -> Self {
+        KnowledgeGapOptions {
+            own_margin: 10.0,
+            class_z_threshold: 0.5,
+            top_gaps: 3,
+            topics: TopicMapping::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MasteryMatrix {
+    pub students: Vec<String>,
+    pub chapters: Vec<String>,
+    pub cells: Vec<Vec<Option<f64>>>,   // [student][chapter] average percentage
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GapAssignment {
+    pub assignment_id: String,
+    pub assignment_name: String,
+    pub score: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KnowledgeGap {
+    pub chapter: String,
+    pub score: f64,
+    pub student_average: f64,
+    pub class_chapter_mean: f64,
+    pub below_own_average: f64,   // Points
+    pub class_z_score: f64,
+    pub severity: f64,            // Combined shortfall used for ranking
+    pub assignments: Vec<GapAssignment>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentKnowledgeGaps {
+    pub student_id: String,
+    pub average: f64,
+    pub gap_count: usize,
+    pub top_gaps: Vec<KnowledgeGap>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KnowledgeGapResult {
+    pub matrix: MasteryMatrix,
+    pub students: Vec<StudentKnowledgeGaps>,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_knowledge_gaps(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: KnowledgeGapOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let topics = options.topics.compile()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let result = compute_knowledge_gaps(&grades, &assignments, &options, &topics);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+pub(crate) fn compute_knowledge_gaps(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &KnowledgeGapOptions,
+    topics: &TopicMapper,
+) -> KnowledgeGapResult {
+    // student -> chapter -> assignments scored
+    let mut evidence: BTreeMap<String, BTreeMap<String, Vec<GapAssignment>>> = BTreeMap::new();
+    let mut student_scores: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    let mut chapters: BTreeSet<String> = BTreeSet::new();
+
+    for grade in grades.iter().filter(|g| g.max_score > 0.0) {
+        let percentage = (grade.score / grade.max_score) * 100.0;
+        student_scores.entry(grade.student_id.clone()).or_default().push(percentage);
+
+        let Some(assignment) = assignments.iter().find(|a| a.id == grade.assignment_id) else {
+            continue;
+        };
+        // Syllabus quizzes and surveys count toward the average but are no chapter to study
+        for chapter in topics.topics_or_other(assignment).into_iter().filter(|c| c != UNMAPPED_TOPIC) {
+            chapters.insert(chapter.clone());
+            evidence
+                .entry(grade.student_id.clone())
+                .or_default()
+                .entry(chapter)
+                .or_default()
+                .push(GapAssignment {
+                    assignment_id: assignment.id.clone(),
+                    assignment_name: assignment.name.clone(),
+                    score: percentage,
+                });
+        }
+    }
+
+    let chapters: Vec<String> = chapters.into_iter().collect();
+    let students: Vec<String> = student_scores.keys().cloned().collect();
+
+    // Student x chapter averages
+    let cells: Vec<Vec<Option<f64>>> = students.iter()
+        .map(|student_id| {
+            chapters.iter()
+                .map(|chapter| {
+                    evidence.get(student_id)
+                        .and_then(|by_chapter| by_chapter.get(chapter))
+                        .map(|items| calculate_mean(&items.iter().map(|i| i.score).collect::<Vec<_>>()))
+                })
+                .collect()
+        })
+        .collect();
+
+    // Class distribution per chapter (over student chapter averages)
+    let class_stats: Vec<(f64, f64)> = (0..chapters.len())
+        .map(|c| {
+            let column: Vec<f64> = cells.iter().filter_map(|row| row[c]).collect();
+            let mean = calculate_mean(&column);
+            (mean, calculate_std_deviation(&column, mean))
+        })
+        .collect();
+
+    let student_gaps: Vec<StudentKnowledgeGaps> = students.iter()
+        .enumerate()
+        .map(|(s, student_id)| {
+            let average = calculate_mean(&student_scores[student_id]);
+
+            let mut gaps: Vec<KnowledgeGap> = chapters.iter()
+                .enumerate()
+                .filter_map(|(c, chapter)| {
+                    let score = cells[s][c]?;
+                    let (class_mean, class_std) = class_stats[c];
+                    let below_own_average = average - score;
+                    let class_z_score = if class_std > 0.0 {
+                        (score - class_mean) / class_std
+                    } else {
+                        0.0
+                    };
+
+                    // A gap must stand out against both the student and the class
+                    if below_own_average < options.own_margin || class_z_score > -options.class_z_threshold {
+                        return None;
+                    }
+
+                    Some(KnowledgeGap {
+                        chapter: chapter.clone(),
+                        score,
+                        student_average: average,
+                        class_chapter_mean: class_mean,
+                        below_own_average,
+                        class_z_score,
+                        severity: below_own_average + (class_mean - score),
+                        assignments: evidence[student_id][chapter].clone(),
+                    })
+                })
+                .collect();
+
+            gaps.sort_by(|a, b| b.severity.partial_cmp(&a.severity).unwrap());
+            let gap_count = gaps.len();
+            gaps.truncate(options.top_gaps);
+
+            StudentKnowledgeGaps {
+                student_id: student_id.clone(),
+                average,
+                gap_count,
+                top_gaps: gaps,
+            }
+        })
+        .collect();
+
+    KnowledgeGapResult {
+        total_students: students.len(),
+        matrix: MasteryMatrix {
+            students,
+            chapters,
+            cells,
+        },
+        students: student_gaps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topics::default_mapper;
+
+    #[test]
+    fn test_gap_requires_shortfall_against_self_and_class() {
+        let assignments: Vec<Assignment> = ["Chapter 1 Quiz", "Chapter 2 Quiz", "Chapter 3 Quiz", "Course Survey"].iter()
+            .enumerate()
+            .map(|(i, name)| Assignment {
+                id: format!("A{}", i + 1),
+                name: name.to_string(),
+                max_score: 100.0,
+                due_date: None,
+            })
+            .collect();
+        let rows = [
+            ("S1", [90.0, 55.0, 88.0, 60.0]),  // Gap in chapter 2; the survey is not a chapter
+            ("S2", [85.0, 80.0, 84.0, 100.0]),
+            ("S3", [88.0, 82.0, 86.0, 100.0]),
+            ("S4", [60.0, 58.0, 40.0, 100.0]),  // Chapter 3 low, but also below own average
+        ];
+        let grades: Vec<Grade> = rows.iter()
+            .flat_map(|(student, scores)| {
+                scores.iter().enumerate().map(move |(i, score)| Grade {
+                    student_id: student.to_string(),
+                    assignment_id: format!("A{}", i + 1),
+                    score: *score,
+                    max_score: 100.0,
+                    submitted_at: None,
+                    due_date: None,
+                })
+            })
+            .collect();
+
+        let result = compute_knowledge_gaps(&grades, &assignments, &KnowledgeGapOptions::default(), default_mapper());
+
+        assert_eq!(result.matrix.chapters, vec!["Chapter 1", "Chapter 2", "Chapter 3"]);
+        assert!((result.matrix.cells[0][1].unwrap() - 55.0).abs() < 1e-9);
+
+        let s1 = &result.students[0];
+        assert_eq!(s1.gap_count, 1);
+        assert_eq!(s1.top_gaps[0].chapter, "Chapter 2");
+        assert_eq!(s1.top_gaps[0].assignments[0].assignment_id, "A2");
+
+        let s2 = &result.students[1];
+        assert_eq!(s2.gap_count, 0);
+    }
+}
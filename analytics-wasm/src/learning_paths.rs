@@ -0,0 +1,401 @@
+// ============================================================================
+// Personalized Learning Paths (ordered study plans per student)
+// ============================================================================
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::assignment_timeline;
+use crate::knowledge_gaps::{compute_knowledge_gaps, KnowledgeGapOptions};
+use crate::objectives::{objective_attainment, OutcomesModel};
+use crate::topics::{TopicMapper, UNMAPPED_TOPIC};
+use crate::{Assignment, Grade};
+
+// Instructor-supplied material, mapped to a chapter and/or an objective
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LearningResource {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,              // "video", "reading", "practice", ...
+    #[serde(default)]
+    pub chapter: Option<String>,
+    #[serde(default)]
+    pub objective_code: Option<String>,
+    #[serde(default)]
+    pub estimated_minutes: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct LearningPathInput {
+    pub resources: Vec<LearningResource>,
+    pub outcomes: OutcomesModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LearningPathOptions {
+    pub gaps: KnowledgeGapOptions,
+    pub mastery_threshold: f64,   // Chapters/objectives below this are study targets
+    pub max_steps: usize,
+}
+
+impl Default for LearningPathOptions {
+    fn default() -> Self {
+        LearningPathOptions {
+            gaps: KnowledgeGapOptions::default(),
+            mastery_threshold: 70.0,
+            max_steps: 8,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudyStep {
+    pub order: usize,
+    pub chapter: Option<String>,
+    pub objective_code: Option<String>,
+    pub resource: Option<LearningResource>,
+    pub action: String,
+    pub reason: String,
+    pub priority: String,   // "high" for flagged gaps, "medium" otherwise
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentLearningPath {
+    pub student_id: String,
+    pub steps: Vec<StudyStep>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LearningPathResult {
+    pub chapter_order: Vec<String>,
+    pub students: Vec<StudentLearningPath>,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn recommend_learning_paths(
+    grades_json: &str,
+    assignments_json: &str,
+    resources_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let input: LearningPathInput = serde_json::from_str(resources_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse resources: {}", e)))?;
+
+    let options: LearningPathOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let topics = options.gaps.topics.compile()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let result = build_learning_paths(&grades, &assignments, &input, &options, &topics);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Chapters in the order their first assignment falls due; unmapped work has no place
+pub(crate) fn chapter_order(grades: &[Grade], assignments: &[Assignment], topics: &TopicMapper) -> Vec<String> {
+    let mut order: Vec<String> = Vec::new();
+    for (assignment_id, _) in assignment_timeline(grades, assignments) {
+        if let Some(assignment) = assignments.iter().find(|a| a.id == assignment_id) {
+            for chapter in topics.topics(assignment) {
+                if chapter != UNMAPPED_TOPIC && !order.contains(&chapter) {
+                    order.push(chapter);
+                }
+            }
+        }
+    }
+    order
+}
+
+// A chapter the student should revisit, and why
+struct StudyTarget {
+    chapter: String,
+    score: f64,
+    flagged_gap: bool,
+    class_mean: Option<f64>,
+    assignments: Vec<String>,
+}
+
+pub(crate) fn build_learning_paths(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    input: &LearningPathInput,
+    options: &LearningPathOptions,
+    topics: &TopicMapper,
+) -> LearningPathResult {
+    let order = chapter_order(grades, assignments, topics);
+    let position = |chapter: &str| order.iter().position(|c| c == chapter).unwrap_or(usize::MAX);
+
+    let gap_options = KnowledgeGapOptions {
+        top_gaps: usize::MAX,
+        ..options.gaps.clone()
+    };
+    let gaps = compute_knowledge_gaps(grades, assignments, &gap_options, topics);
+    let attainment = objective_attainment(grades, &input.outcomes);
+
+    // Objectives reachable from each chapter through assignment alignments
+    let objective_chapters = |code: &str| -> BTreeSet<String> {
+        input.outcomes.alignments.iter()
+            .filter(|a| a.objective_code == code)
+            .filter_map(|a| assignments.iter().find(|x| x.id == a.assignment_id))
+            .flat_map(|a| topics.topics_or_other(a))
+            .collect()
+    };
+
+    let students: Vec<StudentLearningPath> = gaps.matrix.students.iter()
+        .enumerate()
+        .map(|(s, student_id)| {
+            let student_gaps = &gaps.students[s];
+
+            // Flagged gaps plus any chapter below the mastery threshold
+            let mut targets: Vec<StudyTarget> = Vec::new();
+            for (c, chapter) in gaps.matrix.chapters.iter().enumerate() {
+                let Some(score) = gaps.matrix.cells[s][c] else {
+                    continue;
+                };
+                if chapter == UNMAPPED_TOPIC {
+                    continue;
+                }
+                let gap = student_gaps.top_gaps.iter().find(|g| g.chapter == *chapter);
+                if gap.is_none() && score >= options.mastery_threshold {
+                    continue;
+                }
+                targets.push(StudyTarget {
+                    chapter: chapter.clone(),
+                    score,
+                    flagged_gap: gap.is_some(),
+                    class_mean: gap.map(|g| g.class_chapter_mean),
+                    assignments: gap
+                        .map(|g| g.assignments.iter().map(|a| a.assignment_name.clone()).collect())
+                        .unwrap_or_default(),
+                });
+            }
+
+            // Earlier chapters first: later material usually builds on them
+            targets.sort_by_key(|t| position(&t.chapter));
+
+            let mut steps: Vec<StudyStep> = Vec::new();
+            let mut used_resources: BTreeSet<String> = BTreeSet::new();
+
+            for target in &targets {
+                let base_reason = match target.class_mean {
+                    Some(class_mean) => format!(
+                        "Scored {:.0}% in {} against a class mean of {:.0}%{}",
+                        target.score,
+                        target.chapter,
+                        class_mean,
+                        if target.assignments.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" ({})", target.assignments.join(", "))
+                        }
+                    ),
+                    None => format!(
+                        "Scored {:.0}% in {}, below the {:.0}% mastery threshold",
+                        target.score, target.chapter, options.mastery_threshold
+                    ),
+                };
+                let sequencing = match order.iter().position(|c| *c == target.chapter) {
+                    Some(index) if index + 1 < order.len() => {
+                        format!("; it comes before {} in the course", order[index + 1])
+                    }
+                    _ => String::new(),
+                };
+                let priority = if target.flagged_gap { "high" } else { "medium" };
+
+                // Resources for the chapter itself or for objectives assessed in it
+                let resources: Vec<&LearningResource> = input.resources.iter()
+                    .filter(|r| !used_resources.contains(&r.id))
+                    .filter(|r| {
+                        r.chapter.as_deref() == Some(target.chapter.as_str())
+                            || r.objective_code.as_deref()
+                                .is_some_and(|code| objective_chapters(code).contains(&target.chapter))
+                    })
+                    .collect();
+
+                if resources.is_empty() {
+                    steps.push(StudyStep {
+                        order: 0,
+                        chapter: Some(target.chapter.clone()),
+                        objective_code: None,
+                        resource: None,
+                        action: format!("Review {} material and rework its assignments", target.chapter),
+                        reason: format!("{}{}", base_reason, sequencing),
+                        priority: priority.to_string(),
+                    });
+                }
+                for resource in resources {
+                    used_resources.insert(resource.id.clone());
+                    steps.push(StudyStep {
+                        order: 0,
+                        chapter: Some(target.chapter.clone()),
+                        objective_code: resource.objective_code.clone(),
+                        resource: Some(resource.clone()),
+                        action: format!("Work through \"{}\"", resource.title),
+                        reason: format!("{}{}", base_reason, sequencing),
+                        priority: priority.to_string(),
+                    });
+                }
+            }
+
+            // Unmastered objectives whose resources were not already scheduled
+            for objective in &input.outcomes.objectives {
+                let Some((value, _)) = attainment.get(&objective.code).and_then(|m| m.get(student_id)) else {
+                    continue;
+                };
+                if *value >= options.mastery_threshold {
+                    continue;
+                }
+                let resources: Vec<&LearningResource> = input.resources.iter()
+                    .filter(|r| r.objective_code.as_deref() == Some(objective.code.as_str()))
+                    .filter(|r| !used_resources.contains(&r.id))
+                    .collect();
+                for resource in resources {
+                    used_resources.insert(resource.id.clone());
+                    steps.push(StudyStep {
+                        order: 0,
+                        chapter: None,
+                        objective_code: Some(objective.code.clone()),
+                        resource: Some(resource.clone()),
+                        action: format!("Work through \"{}\"", resource.title),
+                        reason: format!(
+                            "Attainment of {} ({}) is {:.0}%, below the {:.0}% mastery threshold",
+                            objective.code, objective.description, value, options.mastery_threshold
+                        ),
+                        priority: "medium".to_string(),
+                    });
+                }
+            }
+
+            // Keep the most urgent steps, then put them back in course order
+            let mut ranked: Vec<(usize, StudyStep)> = steps.into_iter().enumerate().collect();
+            ranked.sort_by_key(|(_, step)| step.priority != "high");
+            ranked.truncate(options.max_steps);
+            ranked.sort_by_key(|(index, _)| *index);
+            let mut steps: Vec<StudyStep> = ranked.into_iter().map(|(_, step)| step).collect();
+            for (i, step) in steps.iter_mut().enumerate() {
+                step.order = i + 1;
+            }
+
+            StudentLearningPath {
+                student_id: student_id.clone(),
+                steps,
+            }
+        })
+        .collect();
+
+    LearningPathResult {
+        chapter_order: order,
+        total_students: students.len(),
+        students,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topics::default_mapper;
+
+    #[test]
+    fn test_study_plan_follows_chapter_order() {
+        let assignments: Vec<Assignment> = [
+            ("A1", "Chapter 2 Quiz", "2024-09-20"),
+            ("A2", "Chapter 1 Quiz", "2024-09-06"),
+            ("A3", "Chapter 3 Quiz", "2024-10-04"),
+            ("A4", "Syllabus Quiz", "2024-09-02"),
+        ].iter()
+            .map(|(id, name, due)| Assignment {
+                id: id.to_string(),
+                name: name.to_string(),
+                max_score: 100.0,
+                due_date: Some(due.to_string()),
+            })
+            .collect();
+        // The failed syllabus quiz maps to no chapter, so it never becomes a step
+        let grades: Vec<Grade> = [("A1", 50.0), ("A2", 60.0), ("A3", 90.0), ("A4", 20.0)].iter()
+            .map(|(id, score)| Grade {
+                student_id: "S1".to_string(),
+                assignment_id: id.to_string(),
+                score: *score,
+                max_score: 100.0,
+                submitted_at: None,
+                due_date: None,
+            })
+            .collect();
+        let input: LearningPathInput = serde_json::from_str(r#"{
+            "resources": [
+                {"id": "R1", "title": "Limits refresher", "chapter": "Chapter 2"}
+            ]
+        }"#).unwrap();
+
+        let result = build_learning_paths(&grades, &assignments, &input, &LearningPathOptions::default(), default_mapper());
+
+        assert_eq!(result.chapter_order, vec!["Chapter 1", "Chapter 2", "Chapter 3"]);
+        let steps = &result.students[0].steps;
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].chapter.as_deref(), Some("Chapter 1"));
+        assert!(steps[0].resource.is_none());
+        assert!(steps[0].reason.contains("comes before Chapter 2"));
+        assert_eq!(steps[1].resource.as_ref().unwrap().id, "R1");
+        assert_eq!(steps[1].order, 2);
+    }
+
+    #[test]
+    fn test_step_limit_keeps_flagged_gaps() {
+        let assignments: Vec<Assignment> = [
+            ("A1", "Chapter 1 Quiz", "2024-09-06"),
+            ("A2", "Chapter 2 Quiz", "2024-09-20"),
+            ("A3", "Chapter 3 Quiz", "2024-10-04"),
+        ].iter()
+            .map(|(id, name, due)| Assignment {
+                id: id.to_string(),
+                name: name.to_string(),
+                max_score: 100.0,
+                due_date: Some(due.to_string()),
+            })
+            .collect();
+        let scores = [
+            ("S1", "A1", 65.0), ("S1", "A2", 20.0), ("S1", "A3", 95.0),
+            ("S2", "A1", 90.0), ("S2", "A2", 90.0), ("S2", "A3", 90.0),
+        ];
+        let grades: Vec<Grade> = scores.iter()
+            .map(|(student, id, score)| Grade {
+                student_id: student.to_string(),
+                assignment_id: id.to_string(),
+                score: *score,
+                max_score: 100.0,
+                submitted_at: None,
+                due_date: None,
+            })
+            .collect();
+        let options = LearningPathOptions {
+            max_steps: 1,
+            ..LearningPathOptions::default()
+        };
+
+        let result = build_learning_paths(&grades, &assignments, &LearningPathInput::default(), &options, default_mapper());
+
+        // Chapter 1 is merely below mastery; the flagged Chapter 2 gap wins the one slot
+        let steps = &result.students[0].steps;
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].chapter.as_deref(), Some("Chapter 2"));
+        assert_eq!(steps[0].priority, "high");
+        assert_eq!(steps[0].order, 1);
+    }
+}
@@ -1,6 +1,42 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 
+pub mod alert_rules;
+pub mod assessment_types;
+pub mod chapter_stats;
+pub mod clustering;
+pub mod comparative;
+pub mod cross_course;
+mod dates;
+pub mod distribution;
+pub mod group_work;
+pub mod interventions;
+pub mod kalman;
+pub mod knowledge_gaps;
+pub mod learning_paths;
+pub mod objectives;
+mod random;
+pub mod risk_diff;
+pub mod risk_model;
+pub mod shrinkage;
+pub mod simulation;
+pub mod smoothing;
+pub mod submission;
+pub mod survival;
+pub mod time_on_task;
+pub mod topic_clusters;
+mod topic_lexicon;
+pub mod topic_tree;
+pub mod topics;
+
+use chapter_stats::{ChapterComparison, ChapterSample, ChapterStatisticsOptions};
+use kalman::{AbilityTrajectory, KalmanOptions};
+use risk_model::RiskModel;
+use shrinkage::ShrinkagePrior;
+use smoothing::SmoothingOptions;
+use topic_tree::{TopicLevelSummary, TopicNode};
+use topics::{TopicMapper, TopicMapping};
+
 // Data structures for gradebook data
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Student {
@@ -26,17 +62,25 @@ pub struct Assignment {
     pub due_date: Option<String>,
 }
 
-// Risk assessment result
-#[derive(Serialize, Deserialize, Debug)]
+// Risk assessment result (older snapshots may lack the shrinkage fields)
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RiskAssessment {
     pub student_id: String,
     pub risk_level: String,  // "high", "medium", "low"
     pub risk_score: f64,     // 0-100
     pub factors: Vec<String>,
     pub recommendations: Vec<String>,
+    #[serde(default)]
+    pub grade_count: usize,
+    #[serde(default)]
+    pub raw_average: f64,        // Student's own average (0-100)
+    #[serde(default)]
+    pub shrunk_average: f64,     // Average pulled toward the class mean by data volume
+    #[serde(default)]
+    pub data_sufficiency: String, // "sufficient", "limited", "insufficient"
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EarlyInterventionResult {
     pub high_risk: Vec<RiskAssessment>,
     pub medium_risk: Vec<RiskAssessment>,
@@ -57,22 +101,82 @@ pub fn analyze_early_intervention(
     let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
     
+    let result = compute_early_intervention(&grades, &assignments, None);
+    
+    // Serialize result
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Early Intervention scored by a trained model instead of the fixed weights
+#[wasm_bindgen]
+pub fn analyze_early_intervention_with_model(
+    grades_json: &str,
+    assignments_json: &str,
+    model_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+    
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+    
+    let model: RiskModel = serde_json::from_str(model_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse model: {}", e)))?;
+    model.validate().map_err(|e| JsValue::from_str(&format!("Invalid model: {}", e)))?;
+    
+    let result = compute_early_intervention(&grades, &assignments, Some(&model));
+    
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+pub(crate) fn compute_early_intervention(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    model: Option<&RiskModel>,
+) -> EarlyInterventionResult {
+    // A trained model scores students on the share of the course it was trained on
+    let window = model
+        .filter(|model| model.observe_fraction < 1.0)
+        .map(|model| risk_model::observed_assignments(grades, assignments, model.observe_fraction));
+    
     // Group grades by student
     let mut student_grades: std::collections::HashMap<String, Vec<&Grade>> = 
         std::collections::HashMap::new();
     
-    for grade in &grades {
-        student_grades
-            .entry(grade.student_id.clone())
-            .or_insert_with(Vec::new)
-            .push(grade);
+    for grade in grades {
+        let list = student_grades.entry(grade.student_id.clone()).or_default();
+        if window.as_ref().is_none_or(|ids| ids.contains(&grade.assignment_id)) {
+            list.push(grade);
+        }
     }
     
+    // Class prior for shrinking sparse averages (a 0/0 grade would make it NaN)
+    let student_scores: Vec<Vec<f64>> = student_grades.values()
+        .map(|list| {
+            list.iter()
+                .filter(|g| g.max_score > 0.0)
+                .map(|g| (g.score / g.max_score) * 100.0)
+                .collect()
+        })
+        .collect();
+    let average_prior = ShrinkagePrior::for_averages(&student_scores);
+    
     // Analyze each student
     let mut assessments: Vec<RiskAssessment> = Vec::new();
     
     for (student_id, student_grade_list) in student_grades.iter() {
-        let assessment = assess_student_risk(student_id, student_grade_list, &assignments);
+        let assessment = match model {
+            Some(model) => model.assess(student_id, student_grade_list, average_prior.as_ref()),
+            None => assess_student_risk(
+                student_id,
+                student_grade_list,
+                assignments,
+                average_prior.as_ref(),
+            ),
+        };
         assessments.push(assessment);
     }
     
@@ -89,16 +193,12 @@ pub fn analyze_early_intervention(
         }
     }
     
-    let result = EarlyInterventionResult {
+    EarlyInterventionResult {
         total_students: student_grades.len(),
         high_risk,
         medium_risk,
         low_risk,
-    };
-    
-    // Serialize result
-    serde_json::to_string(&result)
-        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+    }
 }
 
 // Assess individual student risk
@@ -106,6 +206,7 @@ fn assess_student_risk(
     student_id: &str,
     grades: &[&Grade],
     _assignments: &[Assignment],
+    average_prior: Option<&ShrinkagePrior>,
 ) -> RiskAssessment {
     let mut risk_score = 0.0;
     let mut factors = Vec::new();
@@ -118,6 +219,10 @@ fn assess_student_risk(
             risk_score: 0.0,
             factors: vec!["No grades available".to_string()],
             recommendations: vec!["Monitor student progress".to_string()],
+            grade_count: 0,
+            raw_average: 0.0,
+            shrunk_average: 0.0,
+            data_sufficiency: "insufficient".to_string(),
         };
     }
     
@@ -127,14 +232,29 @@ fn assess_student_risk(
         .sum();
     let avg_percentage = total_score / grades.len() as f64;
     
+    // Shrink toward the class mean so a single bad quiz counts for less
+    let (shrunk_average, reliability) = match average_prior {
+        Some(prior) => {
+            let estimate = prior.shrink_average(avg_percentage, grades.len());
+            (estimate.shrunk, estimate.reliability)
+        }
+        None => (avg_percentage, 1.0),
+    };
+    let data_sufficiency = shrinkage::data_sufficiency(grades.len(), reliability);
+    let average_label = if (shrunk_average - avg_percentage).abs() >= 0.05 {
+        format!("{:.1}% (raw {:.1}% over {} grades)", shrunk_average, avg_percentage, grades.len())
+    } else {
+        format!("{:.1}%", shrunk_average)
+    };
+    
     // Factor 1: Low average score
-    if avg_percentage < 60.0 {
+    if shrunk_average < 60.0 {
         risk_score += 40.0;
-        factors.push(format!("Low average score: {:.1}%", avg_percentage));
+        factors.push(format!("Low average score: {}", average_label));
         recommendations.push("Schedule one-on-one meeting".to_string());
-    } else if avg_percentage < 70.0 {
+    } else if shrunk_average < 70.0 {
         risk_score += 20.0;
-        factors.push(format!("Below average score: {:.1}%", avg_percentage));
+        factors.push(format!("Below average score: {}", average_label));
     }
     
     // Factor 2: Declining trend
@@ -186,6 +306,10 @@ fn assess_student_risk(
         risk_score,
         factors,
         recommendations,
+        grade_count: grades.len(),
+        raw_average: avg_percentage,
+        shrunk_average,
+        data_sufficiency,
     }
 }
 
@@ -220,20 +344,53 @@ pub struct ChapterStats {
     pub std_deviation: f64,
     pub difficulty_level: String,  // "easy", "moderate", "hard", "very_hard"
     pub student_count: usize,
+    pub grade_count: usize,
+    pub ci_lower: Option<f64>,     // Bootstrap interval for avg_score
+    pub ci_upper: Option<f64>,
+    pub bimodality_coefficient: f64,
+    pub likely_bimodal: bool,      // Class split between failing and acing
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnmappedAssignment {
+    pub assignment_id: String,
+    pub assignment_name: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ChapterDifficultyResult {
     pub chapters: Vec<ChapterStats>,
     pub total_chapters: usize,
-    pub hardest_chapter: Option<String>,
-    pub easiest_chapter: Option<String>,
+    pub hardest_chapter: Option<String>,      // Only when significantly below the runner-up
+    pub easiest_chapter: Option<String>,      // Only when significantly above the runner-up
+    pub lowest_mean_chapter: Option<String>,  // Extremes and comparisons leave out "Other"
+    pub highest_mean_chapter: Option<String>,
+    pub comparisons: Vec<ChapterComparison>,
+    pub unmapped_assignments: Vec<UnmappedAssignment>,  // Grouped under "Other"
+    pub tree: Vec<TopicNode>,                 // Unit > chapter > section, for drill-down
+    pub levels: Vec<TopicLevelSummary>,       // Hardest/easiest node per level
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ChapterDifficultyOptions {
+    pub topics: TopicMapping,
+    pub statistics: ChapterStatisticsOptions,
 }
 
 #[wasm_bindgen]
 pub fn analyze_chapter_difficulty(
     grades_json: &str,
     assignments_json: &str,
+) -> Result<String, JsValue> {
+    analyze_chapter_difficulty_with_options(grades_json, assignments_json, "{}")
+}
+
+#[wasm_bindgen]
+pub fn analyze_chapter_difficulty_with_options(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
 ) -> Result<String, JsValue> {
     // Parse input data
     let grades: Vec<Grade> = serde_json::from_str(grades_json)
@@ -242,90 +399,160 @@ pub fn analyze_chapter_difficulty(
     let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
     
-    // Extract chapter from assignment names
-    let mut chapter_data: std::collections::HashMap<String, Vec<f64>> = 
-        std::collections::HashMap::new();
+    let options: ChapterDifficultyOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
     
-    for assignment in &assignments {
-        let chapter = extract_chapter_name(&assignment.name);
+    let topics = options.topics.compile()
+        .map_err(|e| JsValue::from_str(&e))?;
+    
+    let result = compute_chapter_difficulty(&grades, &assignments, &topics, &options.statistics);
+    
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+pub(crate) fn compute_chapter_difficulty(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    topics: &TopicMapper,
+    statistics: &ChapterStatisticsOptions,
+) -> ChapterDifficultyResult {
+    // Scores and contributing assignments per chapter; an assignment may feed several
+    let mut chapter_data: std::collections::BTreeMap<String, (Vec<f64>, Vec<&Grade>, usize)> = 
+        std::collections::BTreeMap::new();
+    let mut unmapped_assignments = Vec::new();
+    
+    for assignment in assignments {
+        let chapters = topics.topics(assignment);
+        if chapters.is_empty() {
+            unmapped_assignments.push(UnmappedAssignment {
+                assignment_id: assignment.id.clone(),
+                assignment_name: assignment.name.clone(),
+            });
+        }
         
         // Get all grades for this assignment
-        let assignment_grades: Vec<f64> = grades.iter()
-            .filter(|g| g.assignment_id == assignment.id)
-            .map(|g| (g.score / g.max_score) * 100.0)
+        let assignment_grades: Vec<&Grade> = grades.iter()
+            .filter(|g| g.assignment_id == assignment.id && g.max_score > 0.0)
             .collect();
         
-        if !assignment_grades.is_empty() {
-            chapter_data.entry(chapter)
-                .or_insert_with(Vec::new)
-                .extend(assignment_grades);
+        if assignment_grades.is_empty() {
+            continue;
+        }
+        for chapter in topics.topics_or_other(assignment) {
+            let entry = chapter_data.entry(chapter).or_default();
+            entry.0.extend(assignment_grades.iter().map(|g| (g.score / g.max_score) * 100.0));
+            entry.1.extend(assignment_grades.iter().copied());
+            entry.2 += 1;
         }
     }
     
-    // Calculate statistics for each chapter
+    // Calculate statistics for each chapter (in name order so the bootstrap is reproducible)
+    let mut rng = random::SeededRng::new(statistics.seed);
+    let mut samples: Vec<ChapterSample> = Vec::new();
     let mut chapters: Vec<ChapterStats> = chapter_data.iter()
-        .map(|(name, scores)| {
+        .map(|(name, (scores, chapter_grades, assignment_count))| {
             let avg = calculate_mean(scores);
             let std_dev = calculate_std_deviation(scores, avg);
             let difficulty = categorize_difficulty(avg, std_dev);
+            let mut per_student: std::collections::BTreeMap<String, Vec<f64>> =
+                std::collections::BTreeMap::new();
+            for grade in chapter_grades {
+                per_student
+                    .entry(grade.student_id.clone())
+                    .or_default()
+                    .push((grade.score / grade.max_score) * 100.0);
+            }
+            let interval = chapter_stats::bootstrap_mean_interval(scores, statistics, &mut rng);
+            let (skewness, kurtosis) = distribution::calculate_shape(scores, avg);
+            let bimodality = distribution::calculate_bimodality_coefficient(skewness, kurtosis, scores.len());
+            
+            // "Other" is a catch-all, not a chapter to rank or test against
+            if name != topics::UNMAPPED_TOPIC {
+                samples.push(ChapterSample {
+                    name: name.clone(),
+                    student_means: per_student.iter()
+                        .map(|(student_id, list)| (student_id.clone(), calculate_mean(list)))
+                        .collect(),
+                });
+            }
             
             ChapterStats {
                 chapter_name: name.clone(),
-                assignment_count: scores.len(),
+                assignment_count: *assignment_count,
                 avg_score: avg,
                 std_deviation: std_dev,
                 difficulty_level: difficulty,
-                student_count: scores.len(),
+                student_count: per_student.len(),
+                grade_count: scores.len(),
+                ci_lower: interval.map(|(lower, _)| lower),
+                ci_upper: interval.map(|(_, upper)| upper),
+                bimodality_coefficient: bimodality,
+                likely_bimodal: bimodality > distribution::BIMODALITY_THRESHOLD,
             }
         })
         .collect();
+    let comparisons = chapter_stats::compare_chapters(&samples, statistics);
     
     // Sort by average score (ascending = hardest first)
     chapters.sort_by(|a, b| a.avg_score.partial_cmp(&b.avg_score).unwrap());
+    let ranked: Vec<&ChapterStats> = chapters.iter()
+        .filter(|c| c.chapter_name != topics::UNMAPPED_TOPIC)
+        .collect();
     
-    let hardest = chapters.first().map(|c| c.chapter_name.clone());
-    let easiest = chapters.last().map(|c| c.chapter_name.clone());
+    // An extreme only counts when it differs significantly from the next chapter in line
+    let separated = |a: &ChapterStats, b: &ChapterStats| {
+        comparisons.iter().any(|c| {
+            c.significant
+                && ((c.chapter_a == a.chapter_name && c.chapter_b == b.chapter_name)
+                    || (c.chapter_a == b.chapter_name && c.chapter_b == a.chapter_name))
+        })
+    };
+    let hardest = match ranked.as_slice() {
+        [first, second, ..] if separated(first, second) => Some(first.chapter_name.clone()),
+        _ => None,
+    };
+    let easiest = match ranked.as_slice() {
+        [.., second, last] if separated(last, second) => Some(last.chapter_name.clone()),
+        _ => None,
+    };
+    let lowest_mean_chapter = ranked.first().map(|c| c.chapter_name.clone());
+    let highest_mean_chapter = ranked.last().map(|c| c.chapter_name.clone());
+    let (tree, levels) = topic_tree::build_topic_tree(grades, assignments, topics);
     
-    let result = ChapterDifficultyResult {
+    ChapterDifficultyResult {
         total_chapters: chapters.len(),
         hardest_chapter: hardest,
         easiest_chapter: easiest,
+        lowest_mean_chapter,
+        highest_mean_chapter,
+        comparisons,
         chapters,
-    };
-    
-    serde_json::to_string(&result)
-        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+        unmapped_assignments,
+        tree,
+        levels,
+    }
 }
 
-// Extract chapter name from assignment name
-fn extract_chapter_name(assignment_name: &str) -> String {
-    // Try to extract chapter number/name from assignment name
-    // Common patterns: "Chapter 1", "Ch 1", "Unit 1", "Week 1", etc.
-    
-    let patterns = [
-        ("Chapter ", "Chapter "),
-        ("Ch ", "Chapter "),
-        ("Unit ", "Unit "),
-        ("Week ", "Week "),
-        ("Module ", "Module "),
-    ];
-    
-    for (pattern, replacement) in &patterns {
-        if let Some(idx) = assignment_name.find(pattern) {
-            let after = &assignment_name[idx + pattern.len()..];
-            // Extract number or name until space or special char
-            let chapter_id: String = after.chars()
-                .take_while(|c| c.is_alphanumeric())
-                .collect();
-            
-            if !chapter_id.is_empty() {
-                return format!("{}{}", replacement, chapter_id);
-            }
-        }
+// Extract chapter name from assignment name using the mapper's patterns
+fn extract_chapter_name(assignment_name: &str, topics: &TopicMapper) -> String {
+    topics
+        .name_topics(assignment_name)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| topics::UNMAPPED_TOPIC.to_string())
+}
+
+// Each student's grades per assignment, in input order, for constant-time lookups
+fn index_grades(grades: &[Grade]) -> std::collections::HashMap<(&str, &str), Vec<&Grade>> {
+    let mut index: std::collections::HashMap<(&str, &str), Vec<&Grade>> = std::collections::HashMap::new();
+    for grade in grades {
+        index
+            .entry((grade.student_id.as_str(), grade.assignment_id.as_str()))
+            .or_default()
+            .push(grade);
     }
-    
-    // If no pattern found, use "Other"
-    "Other".to_string()
+    index
 }
 
 // Calculate mean of scores
@@ -352,11 +579,61 @@ fn calculate_std_deviation(scores: &[f64], mean: f64) -> f64 {
     variance.sqrt()
 }
 
+// Quantile (0-1) of an ascending-sorted slice with linear interpolation
+fn calculate_quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+    
+    sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+}
+
+// Pearson correlation of paired samples (0 when undefined)
+fn calculate_correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return 0.0;
+    }
+
+    let mean_x = calculate_mean(&xs[..n]);
+    let mean_y = calculate_mean(&ys[..n]);
+    let mut covariance = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+
+    for i in 0..n {
+        let dx = xs[i] - mean_x;
+        let dy = ys[i] - mean_y;
+        covariance += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    if var_x == 0.0 || var_y == 0.0 {
+        return 0.0;
+    }
+    covariance / (var_x * var_y).sqrt()
+}
+
 // Categorize difficulty based on average score and std deviation
-fn categorize_difficulty(avg_score: f64, _std_dev: f64) -> String {
-    if avg_score >= 85.0 {
+// A wide spread holds a chapter back a level when many students would still fail (<60)
+fn categorize_difficulty(avg_score: f64, std_dev: f64) -> String {
+    let failing_share = if std_dev > 0.0 {
+        chapter_stats::normal_cdf((60.0 - avg_score) / std_dev)
+    } else if avg_score < 60.0 {
+        1.0
+    } else {
+        0.0
+    };
+    
+    if avg_score >= 85.0 && failing_share < 0.10 {
         "easy".to_string()
-    } else if avg_score >= 70.0 {
+    } else if avg_score >= 70.0 && failing_share < 0.25 {
         "moderate".to_string()
     } else if avg_score >= 60.0 {
         "hard".to_string()
@@ -365,6 +642,24 @@ fn categorize_difficulty(avg_score: f64, _std_dev: f64) -> String {
     }
 }
 
+// Letter grades from best to worst
+const LETTERS: [&str; 5] = ["A", "B", "C", "D", "F"];
+
+// Map a final percentage to a letter grade
+fn letter_grade(percentage: f64) -> &'static str {
+    if percentage >= 90.0 {
+        "A"
+    } else if percentage >= 80.0 {
+        "B"
+    } else if percentage >= 70.0 {
+        "C"
+    } else if percentage >= 60.0 {
+        "D"
+    } else {
+        "F"
+    }
+}
+
 // ============================================================================
 // INSIGHT 3: Assessment Quality Analysis
 // ============================================================================
@@ -497,8 +792,11 @@ fn calculate_cronbachs_alpha(grades: &[Grade], assignments: &[Assignment]) -> f6
     let k = assignments.len() as f64;
     let alpha = (k / (k - 1.0)) * (1.0 - (sum_item_variances / total_variance));
     
-    // Clamp between 0 and 1
-    alpha.max(0.0).min(1.0)
+    // Clamp between 0 and 1 (zero total variance yields NaN)
+    if alpha.is_nan() {
+        return 0.0;
+    }
+    alpha.clamp(0.0, 1.0)
 }
 
 // Calculate variance
@@ -508,11 +806,9 @@ fn calculate_variance(scores: &[f64]) -> f64 {
     }
     
     let mean = scores.iter().sum::<f64>() / scores.len() as f64;
-    let variance = scores.iter()
+    scores.iter()
         .map(|score| (score - mean).powi(2))
-        .sum::<f64>() / (scores.len() - 1) as f64;
-    
-    variance
+        .sum::<f64>() / (scores.len() - 1) as f64
 }
 
 // Rate reliability based on Cronbach's alpha
@@ -589,14 +885,14 @@ fn rate_item_quality(difficulty: f64, discrimination: f64) -> (String, String) {
         );
     }
     
-    if difficulty < 0.2 || difficulty > 0.9 {
+    if !(0.2..=0.9).contains(&difficulty) {
         return (
             "fair".to_string(),
             "Extreme difficulty - most students got it very wrong or very right".to_string()
         );
     }
     
-    if discrimination >= 0.4 && difficulty >= 0.3 && difficulty <= 0.7 {
+    if discrimination >= 0.4 && (0.3..=0.7).contains(&difficulty) {
         return (
             "excellent".to_string(),
             "Well-designed item with good discrimination".to_string()
@@ -617,10 +913,14 @@ fn rate_item_quality(difficulty: f64, discrimination: f64) -> (String, String) {
 pub struct ProgressionMetrics {
     pub overall_trend: String,      // "improving", "declining", "stable"
     pub trend_strength: f64,         // 0-1 (how strong the trend is)
-    pub velocity: f64,               // Points per assignment
-    pub momentum: String,            // "accelerating", "decelerating", "steady"
+    pub velocity: f64,               // Points per assignment (raw least-squares slope)
+    pub shrunk_velocity: f64,        // Slope pulled toward the class slope by data volume
+    pub data_sufficiency: String,    // "sufficient", "limited", "insufficient"
+    pub momentum: String,            // "accelerating", "decelerating", "steady" (from smoothed curve)
     pub current_performance: f64,    // Current average (0-100)
     pub projected_performance: f64,  // Projected next score
+    pub raw_trajectory: Vec<Option<f64>>,  // Per assignment in course order; None for missing work
+    pub smoothed_trajectory: Vec<f64>,     // Over graded assignments only
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -629,18 +929,47 @@ pub struct LearningProgressionResult {
     pub class_average_trend: String,
     pub class_velocity: f64,
     pub total_students: usize,
+    pub smoothing_method: String,
+    pub class_raw_trajectory: Vec<Option<f64>>,  // Class mean per assignment, aligned with raw_trajectory
+    pub class_smoothed_trajectory: Vec<f64>,     // Over assignments with any grade
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct StudentProgression {
     pub student_id: String,
     pub metrics: ProgressionMetrics,
+    pub ability: Option<AbilityTrajectory>,  // Only with the Kalman engine
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressionEngine {
+    #[default]
+    Regression,   // Least-squares slope over the per-assignment series
+    Kalman,       // Latent-ability state-space model
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ProgressionOptions {
+    pub engine: ProgressionEngine,
+    pub smoothing: SmoothingOptions,
+    pub kalman: KalmanOptions,
 }
 
 #[wasm_bindgen]
 pub fn analyze_learning_progression(
     grades_json: &str,
     assignments_json: &str,
+) -> Result<String, JsValue> {
+    analyze_learning_progression_with_options(grades_json, assignments_json, "{}")
+}
+
+#[wasm_bindgen]
+pub fn analyze_learning_progression_with_options(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
 ) -> Result<String, JsValue> {
     // Parse input data
     let grades: Vec<Grade> = serde_json::from_str(grades_json)
@@ -649,28 +978,74 @@ pub fn analyze_learning_progression(
     let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
     
+    let options: ProgressionOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+    
+    let result = compute_learning_progression(&grades, &assignments, &options);
+    
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+pub(crate) fn compute_learning_progression(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &ProgressionOptions,
+) -> LearningProgressionResult {
     // Group grades by student
     let mut student_grades: std::collections::HashMap<String, Vec<&Grade>> = 
         std::collections::HashMap::new();
     
-    for grade in &grades {
+    for grade in grades {
         student_grades
             .entry(grade.student_id.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(grade);
     }
     
+    // Course order shared by every trajectory
+    let timeline = dates::assignment_timeline(grades, assignments);
+    
+    // Class prior for shrinking per-student slopes, fit on the same series
+    // calculate_progression_metrics takes the slope of
+    let student_scores: Vec<Vec<f64>> = student_grades.values()
+        .map(|list| timeline_trajectory(list, &timeline).into_iter().flatten().collect())
+        .collect();
+    let slope_prior = shrinkage::slope_prior(&student_scores);
+    
+    // Latent ability trajectories when the Kalman engine is selected
+    let latent = match options.engine {
+        ProgressionEngine::Kalman => Some(kalman::track_latent_ability(grades, assignments, &options.kalman)),
+        ProgressionEngine::Regression => None,
+    };
+    
     // Analyze each student's progression
     let mut student_progressions: Vec<StudentProgression> = Vec::new();
     let mut all_velocities: Vec<f64> = Vec::new();
     
     for (student_id, student_grade_list) in student_grades.iter() {
-        let metrics = calculate_progression_metrics(student_grade_list, &assignments);
+        let ability = latent.as_ref().and_then(|result| {
+            result.students.iter().find(|t| t.student_id == *student_id).cloned()
+        });
+        
+        let metrics = match (&latent, &ability) {
+            (Some(result), Some(trajectory)) => {
+                let raw_scores = timeline_trajectory(student_grade_list, &timeline);
+                kalman::progression_metrics(trajectory, raw_scores, result.prior_variance)
+            }
+            _ => calculate_progression_metrics(
+                student_grade_list,
+                &timeline,
+                &options.smoothing,
+                slope_prior.as_ref(),
+            ),
+        };
         all_velocities.push(metrics.velocity);
         
         student_progressions.push(StudentProgression {
             student_id: student_id.clone(),
             metrics,
+            ability,
         });
     }
     
@@ -689,37 +1064,68 @@ pub fn analyze_learning_progression(
         "stable".to_string()
     };
     
-    let result = LearningProgressionResult {
+    // Class trajectory: mean percentage per assignment, in course order
+    let all_grades: Vec<&Grade> = grades.iter().collect();
+    let class_raw_trajectory = timeline_trajectory(&all_grades, &timeline);
+    let class_points: Vec<f64> = class_raw_trajectory.iter().flatten().copied().collect();
+    let class_smoothed_trajectory = smoothing::smooth_series(&class_points, &options.smoothing);
+    
+    LearningProgressionResult {
         total_students: student_progressions.len(),
         class_average_trend,
         class_velocity,
         student_progressions,
-    };
-    
-    serde_json::to_string(&result)
-        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+        smoothing_method: options.smoothing.method.as_str().to_string(),
+        class_raw_trajectory,
+        class_smoothed_trajectory,
+    }
+}
+
+// Mean percentage per timeline assignment; None where no grade exists
+fn timeline_trajectory(grades: &[&Grade], timeline: &[(String, Option<f64>)]) -> Vec<Option<f64>> {
+    timeline.iter()
+        .map(|(assignment_id, _)| {
+            let scores: Vec<f64> = grades.iter()
+                .filter(|g| g.assignment_id == *assignment_id && g.max_score > 0.0)
+                .map(|g| (g.score / g.max_score) * 100.0)
+                .collect();
+            
+            if scores.is_empty() {
+                None
+            } else {
+                Some(calculate_mean(&scores))
+            }
+        })
+        .collect()
 }
 
 // Calculate progression metrics for a student
 fn calculate_progression_metrics(
     grades: &[&Grade],
-    _assignments: &[Assignment],
+    timeline: &[(String, Option<f64>)],
+    smoothing: &SmoothingOptions,
+    slope_prior: Option<&ShrinkagePrior>,
 ) -> ProgressionMetrics {
-    if grades.len() < 2 {
+    // Percentages in course order; the trend skips missing work
+    let raw_trajectory = timeline_trajectory(grades, timeline);
+    let scores: Vec<f64> = raw_trajectory.iter().flatten().copied().collect();
+    
+    if scores.len() < 2 {
         return ProgressionMetrics {
             overall_trend: "insufficient_data".to_string(),
             trend_strength: 0.0,
             velocity: 0.0,
+            shrunk_velocity: 0.0,
+            data_sufficiency: "insufficient".to_string(),
             momentum: "unknown".to_string(),
             current_performance: 0.0,
             projected_performance: 0.0,
+            raw_trajectory,
+            smoothed_trajectory: scores,
         };
     }
     
-    // Convert grades to percentages and sort by time (assuming order)
-    let scores: Vec<f64> = grades.iter()
-        .map(|g| (g.score / g.max_score) * 100.0)
-        .collect();
+    let smoothed = smoothing::smooth_series(&scores, smoothing);
     
     // Calculate linear regression for trend
     let n = scores.len() as f64;
@@ -747,47 +1153,72 @@ fn calculate_progression_metrics(
     // Velocity = slope (points per assignment)
     let velocity = slope;
     
-    // Determine trend
-    let overall_trend = if velocity > 2.0 {
-        "improving".to_string()
-    } else if velocity < -2.0 {
-        "declining".to_string()
-    } else {
-        "stable".to_string()
+    // Shrink the slope toward the class slope; few points means a noisy fit
+    let (shrunk_velocity, reliability) = match slope_prior {
+        Some(prior) if denominator > 0.0 => {
+            let estimate = prior.shrink(velocity, prior.noise_variance / denominator);
+            (estimate.shrunk, estimate.reliability)
+        }
+        _ => (velocity, 1.0),
     };
+    let data_sufficiency = shrinkage::data_sufficiency(scores.len(), reliability);
+    
+    // Determine trend
+    let overall_trend = trend_label(shrunk_velocity);
     
     // Trend strength (R-squared approximation)
-    let trend_strength = (velocity.abs() / 10.0).min(1.0);
+    let trend_strength = (shrunk_velocity.abs() / 10.0).min(1.0);
     
-    // Calculate momentum (acceleration)
-    let momentum = if scores.len() >= 3 {
-        let recent_slope = calculate_recent_slope(&scores, 3);
-        let overall_slope = velocity;
-        
-        if recent_slope > overall_slope + 1.0 {
-            "accelerating".to_string()
-        } else if recent_slope < overall_slope - 1.0 {
-            "decelerating".to_string()
-        } else {
-            "steady".to_string()
-        }
-    } else {
-        "steady".to_string()
-    };
+    // Calculate momentum (acceleration) on the smoothed curve so a single
+    // outlier doesn't flip it
+    let momentum = momentum_label(&smoothed);
     
     // Current performance (last score)
     let current_performance = *scores.last().unwrap_or(&0.0);
     
     // Projected performance (linear extrapolation)
-    let projected_performance = (current_performance + velocity).max(0.0).min(100.0);
+    let projected_performance = (current_performance + shrunk_velocity).clamp(0.0, 100.0);
     
     ProgressionMetrics {
         overall_trend,
         trend_strength,
         velocity,
+        shrunk_velocity,
+        data_sufficiency,
         momentum,
         current_performance,
         projected_performance,
+        raw_trajectory,
+        smoothed_trajectory: smoothed,
+    }
+}
+
+// Label a per-assignment slope as a trend
+fn trend_label(velocity: f64) -> String {
+    if velocity > 2.0 {
+        "improving".to_string()
+    } else if velocity < -2.0 {
+        "declining".to_string()
+    } else {
+        "stable".to_string()
+    }
+}
+
+// Compare the recent slope of a smoothed curve with its overall slope
+fn momentum_label(smoothed: &[f64]) -> String {
+    if smoothed.len() < 3 {
+        return "steady".to_string();
+    }
+    
+    let recent_slope = calculate_recent_slope(smoothed, 3);
+    let overall_slope = calculate_recent_slope(smoothed, smoothed.len());
+    
+    if recent_slope > overall_slope + 1.0 {
+        "accelerating".to_string()
+    } else if recent_slope < overall_slope - 1.0 {
+        "decelerating".to_string()
+    } else {
+        "steady".to_string()
     }
 }
 
@@ -797,11 +1228,8 @@ fn calculate_recent_slope(scores: &[f64], n: usize) -> f64 {
         return 0.0;
     }
     
-    let recent: Vec<f64> = scores.iter()
-        .rev()
-        .take(n)
-        .copied()
-        .collect();
+    // Last N scores in chronological order
+    let recent: Vec<f64> = scores[scores.len() - n..].to_vec();
     
     if recent.len() < 2 {
         return 0.0;
@@ -871,7 +1299,7 @@ pub fn analyze_performance_patterns(
     for grade in &grades {
         student_grades
             .entry(grade.student_id.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(grade);
     }
     
@@ -1021,7 +1449,7 @@ mod tests {
 
     #[test]
     fn test_risk_assessment() {
-        let grades = vec![
+        let grades = [
             Grade {
                 student_id: "S1".to_string(),
                 assignment_id: "A1".to_string(),
@@ -1043,7 +1471,7 @@ mod tests {
         let grade_refs: Vec<&Grade> = grades.iter().collect();
         let assignments = vec![];
         
-        let assessment = assess_student_risk("S1", &grade_refs, &assignments);
+        let assessment = assess_student_risk("S1", &grade_refs, &assignments, None);
         
         // Average is 47.5%, which triggers 40 points (low score)
         // This puts it in "medium" risk category (40-69 points)
@@ -1054,10 +1482,13 @@ mod tests {
     
     #[test]
     fn test_chapter_extraction() {
-        assert_eq!(extract_chapter_name("Chapter 1 Quiz"), "Chapter 1");
-        assert_eq!(extract_chapter_name("Ch 2 Assignment"), "Chapter 2");
-        assert_eq!(extract_chapter_name("Unit 3 Test"), "Unit 3");
-        assert_eq!(extract_chapter_name("Random Assignment"), "Other");
+        let topics = topics::default_mapper();
+        assert_eq!(extract_chapter_name("Chapter 1 Quiz", topics), "Chapter 1");
+        assert_eq!(extract_chapter_name("Ch 2 Assignment", topics), "Chapter 2");
+        assert_eq!(extract_chapter_name("Unit 3 Test", topics), "Unit 3");
+        assert_eq!(extract_chapter_name("Random Assignment", topics), "Other");
+        assert_eq!(extract_chapter_name("Ch.3 Reading Check", topics), "Chapter 3");
+        assert_eq!(extract_chapter_name("Lecture 4 Exit Ticket", topics), "Lecture 4");
     }
     
     #[test]
@@ -1107,13 +1538,13 @@ mod tests {
         ];
         
         let reliability = calculate_cronbachs_alpha(&grades, &assignments);
-        assert!(reliability >= 0.0 && reliability <= 1.0);
+        assert!((0.0..=1.0).contains(&reliability));
     }
     
     #[test]
     fn test_learning_progression() {
         // Test improving trend
-        let grades = vec![
+        let grades = [
             Grade {
                 student_id: "S1".to_string(),
                 assignment_id: "A1".to_string(),
@@ -1141,17 +1572,30 @@ mod tests {
         ];
         
         let grade_refs: Vec<&Grade> = grades.iter().collect();
-        let assignments = vec![];
+        let timeline = dates::assignment_timeline(&grades, &[]);
         
-        let metrics = calculate_progression_metrics(&grade_refs, &assignments);
+        let metrics = calculate_progression_metrics(&grade_refs, &timeline, &SmoothingOptions::default(), None);
         
         assert_eq!(metrics.overall_trend, "improving");
         assert!(metrics.velocity > 0.0);
+        
+        // A skipped assignment leaves a gap so students line up with the class
+        let with_gap: Vec<Grade> = grades.iter()
+            .cloned()
+            .chain(grades.iter()
+                .filter(|g| g.assignment_id != "A2")
+                .map(|g| Grade { student_id: "S2".to_string(), ..g.clone() }))
+            .collect();
+        let result = compute_learning_progression(&with_gap, &[], &ProgressionOptions::default());
+        let s2 = result.student_progressions.iter().find(|p| p.student_id == "S2").unwrap();
+        
+        assert_eq!(s2.metrics.raw_trajectory, vec![Some(60.0), None, Some(80.0)]);
+        assert_eq!(result.class_raw_trajectory, vec![Some(60.0), Some(70.0), Some(80.0)]);
     }
     
     #[test]
     fn test_performance_patterns() {
-        let grades = vec![
+        let grades = [
             Grade {
                 student_id: "S1".to_string(),
                 assignment_id: "A1".to_string(),
@@ -1186,4 +1630,154 @@ mod tests {
         assert!(pattern.consistency_score <= 1.0);
     }
 
+    #[test]
+    fn test_risk_assessment_shrinks_sparse_average() {
+        let grade = |student: &str, assignment: &str, score: f64| Grade {
+            student_id: student.to_string(),
+            assignment_id: assignment.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        };
+        let class_scores = vec![
+            vec![40.0],
+            vec![82.0, 78.0, 85.0, 80.0],
+            vec![70.0, 76.0, 74.0, 72.0],
+            vec![90.0, 88.0, 93.0, 91.0],
+        ];
+        let prior = ShrinkagePrior::for_averages(&class_scores).unwrap();
+        
+        let sparse = [grade("S1", "A1", 40.0)];
+        let sparse_refs: Vec<&Grade> = sparse.iter().collect();
+        let assessment = assess_student_risk("S1", &sparse_refs, &[], Some(&prior));
+        
+        assert_eq!(assessment.raw_average, 40.0);
+        assert!(assessment.shrunk_average > assessment.raw_average);
+        assert_eq!(assessment.data_sufficiency, "insufficient");
+        
+        // Another student's 0/0 grade must not poison the class prior
+        let mut grades: Vec<Grade> = ["A1", "A2", "A3"].iter()
+            .flat_map(|a| [grade("S1", a, 10.0), grade("S2", a, 85.0), grade("S3", a, 80.0)])
+            .collect();
+        let baseline = compute_early_intervention(&grades, &[], None);
+        assert_eq!(baseline.medium_risk.len(), 1);
+        grades.push(Grade { max_score: 0.0, ..grade("S2", "A4", 0.0) });
+        let result = compute_early_intervention(&grades, &[], None);
+        assert_eq!(result.medium_risk.len(), 1);
+        assert_eq!(result.medium_risk[0].student_id, "S1");
+        assert!(result.medium_risk[0].shrunk_average.is_finite());
+    }
+
+    #[test]
+    fn test_chapter_difficulty_with_topic_mapping() {
+        let assignment = |id: &str, name: &str| Assignment {
+            id: id.to_string(),
+            name: name.to_string(),
+            max_score: 100.0,
+            due_date: None,
+        };
+        let assignments = vec![
+            assignment("A1", "Chapter 1 Quiz"),
+            assignment("A2", "Midterm"),
+            assignment("A3", "Syllabus Quiz"),
+        ];
+        let grades: Vec<Grade> = [("A1", 90.0), ("A2", 60.0), ("A3", 100.0)].iter()
+            .map(|(assignment_id, score)| Grade {
+                student_id: "S1".to_string(),
+                assignment_id: assignment_id.to_string(),
+                score: *score,
+                max_score: 100.0,
+                submitted_at: None,
+                due_date: None,
+            })
+            .collect();
+        let mapping: TopicMapping = serde_json::from_str(
+            r#"{"overrides": {"A2": ["Chapter 1", "Chapter 2"]}}"#,
+        ).unwrap();
+        
+        let result = compute_chapter_difficulty(
+            &grades,
+            &assignments,
+            &mapping.compile().unwrap(),
+            &ChapterStatisticsOptions::default(),
+        );
+        
+        let chapter_1 = result.chapters.iter().find(|c| c.chapter_name == "Chapter 1").unwrap();
+        assert_eq!(chapter_1.assignment_count, 2);
+        assert!((chapter_1.avg_score - 75.0).abs() < 1e-9);
+        assert_eq!(result.lowest_mean_chapter.as_deref(), Some("Chapter 2"));
+        assert_eq!(result.highest_mean_chapter.as_deref(), Some("Chapter 1"));
+        assert!(result.chapters.iter().any(|c| c.chapter_name == "Other"));
+        assert_eq!(result.unmapped_assignments.len(), 1);
+        assert_eq!(result.unmapped_assignments[0].assignment_id, "A3");
+    }
+
+    #[test]
+    fn test_hardest_chapter_requires_significance() {
+        let assignments: Vec<Assignment> = ["Chapter 1 Quiz", "Chapter 2 Quiz", "Chapter 3 Quiz"].iter()
+            .enumerate()
+            .map(|(i, name)| Assignment {
+                id: format!("A{}", i + 1),
+                name: name.to_string(),
+                max_score: 100.0,
+                due_date: None,
+            })
+            .collect();
+        // Chapter 1 and 2 differ by a fraction of a point; Chapter 3 splits the class
+        let grades: Vec<Grade> = (0..12)
+            .flat_map(|s| {
+                let chapter_1 = 70.0 + (s * 7 % 11) as f64;
+                let chapter_2 = chapter_1 + [0.5, -1.0, 1.0, -0.5][s % 4];
+                let chapter_3 = if s % 2 == 0 { 95.0 + (s % 5) as f64 } else { (s % 5) as f64 };
+                [chapter_1, chapter_2, chapter_3].into_iter().enumerate().map(move |(i, score)| Grade {
+                    student_id: format!("S{}", s + 1),
+                    assignment_id: format!("A{}", i + 1),
+                    score,
+                    max_score: 100.0,
+                    submitted_at: None,
+                    due_date: None,
+                })
+            })
+            .collect();
+        
+        let result = compute_chapter_difficulty(
+            &grades,
+            &assignments,
+            topics::default_mapper(),
+            &ChapterStatisticsOptions::default(),
+        );
+        
+        assert_eq!(result.comparisons.len(), 3);
+        assert!(result.comparisons.iter().all(|c| c.test == "paired"));
+        assert_eq!(result.easiest_chapter, None);
+        assert!(result.highest_mean_chapter.is_some());
+        
+        let chapter_1 = result.chapters.iter().find(|c| c.chapter_name == "Chapter 1").unwrap();
+        let (lower, upper) = (chapter_1.ci_lower.unwrap(), chapter_1.ci_upper.unwrap());
+        assert!(lower < chapter_1.avg_score && chapter_1.avg_score < upper);
+        
+        let chapter_3 = result.chapters.iter().find(|c| c.chapter_name == "Chapter 3").unwrap();
+        assert!(chapter_3.likely_bimodal);
+        assert!(!chapter_1.likely_bimodal);
+        assert_eq!(chapter_3.difficulty_level, "very_hard");
+        
+        // Two clearly separated chapters: the lower one is reported
+        let clear: Vec<Grade> = grades.iter()
+            .filter(|g| g.assignment_id != "A3")
+            .map(|g| Grade {
+                score: if g.assignment_id == "A2" { g.score - 20.0 } else { g.score },
+                ..g.clone()
+            })
+            .collect();
+        let result = compute_chapter_difficulty(
+            &clear,
+            &assignments,
+            topics::default_mapper(),
+            &ChapterStatisticsOptions::default(),
+        );
+        assert_eq!(result.hardest_chapter.as_deref(), Some("Chapter 2"));
+        assert_eq!(result.easiest_chapter.as_deref(), Some("Chapter 1"));
+    }
+
 }
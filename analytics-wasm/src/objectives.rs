@@ -0,0 +1,325 @@
+// ============================================================================
+// Learning Objective Mastery (course outcomes model + attainment analysis)
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{calculate_mean, index_grades, Assignment, Grade};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LearningObjective {
+    pub code: String,           // e.g. "ABET-1", "CLO-3"
+    pub description: String,
+}
+
+// Links an assignment (or one question within it) to an objective
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ObjectiveAlignment {
+    pub objective_code: String,
+    pub assignment_id: String,
+    #[serde(default)]
+    pub question_id: Option<String>,
+    #[serde(default = "default_alignment_weight")]
+    pub weight: f64,
+}
+
+fn default_alignment_weight() -> f64 {
+    1.0
+}
+
+// Question-level score, for alignments finer than a whole assignment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuestionScore {
+    pub student_id: String,
+    pub assignment_id: String,
+    pub question_id: String,
+    pub score: f64,
+    pub max_score: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct OutcomesModel {
+    pub objectives: Vec<LearningObjective>,
+    pub alignments: Vec<ObjectiveAlignment>,
+    pub question_scores: Vec<QuestionScore>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MasteryOptions {
+    pub mastery_threshold: f64,    // Attainment (0-100) a student needs to master an objective
+    pub class_target: f64,         // Share of students (0-1) that must master it
+}
+
+impl Default for MasteryOptions {
+    fn default() -> Self {
+        MasteryOptions {
+            mastery_threshold: 70.0,
+            class_target: 0.7,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ObjectiveAttainment {
+    pub objective_code: String,
+    pub attainment: Option<f64>,   // Weighted mean of aligned items (0-100); None if unassessed
+    pub mastered: bool,
+    pub evidence_count: usize,     // Aligned items the student has a score for
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentObjectiveMastery {
+    pub student_id: String,
+    pub objectives: Vec<ObjectiveAttainment>,
+    pub mastered_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClassObjectiveMastery {
+    pub objective_code: String,
+    pub description: String,
+    pub aligned_items: usize,
+    pub students_assessed: usize,
+    pub mean_attainment: f64,
+    pub mastery_rate: f64,         // 0-1 of assessed students
+    pub target_met: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ObjectiveMasteryResult {
+    pub objectives: Vec<ClassObjectiveMastery>,
+    pub students: Vec<StudentObjectiveMastery>,
+    pub mastery_threshold: f64,
+    pub warnings: Vec<String>,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_objective_mastery(
+    grades_json: &str,
+    assignments_json: &str,
+    outcomes_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let outcomes: OutcomesModel = serde_json::from_str(outcomes_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse outcomes: {}", e)))?;
+
+    let options: MasteryOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = compute_objective_mastery(&grades, &assignments, &outcomes, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Per-student weighted attainment of each objective: code -> student -> (attainment, evidence)
+pub(crate) fn objective_attainment(
+    grades: &[Grade],
+    outcomes: &OutcomesModel,
+) -> BTreeMap<String, BTreeMap<String, (f64, usize)>> {
+    let students: BTreeSet<&str> = grades.iter()
+        .map(|g| g.student_id.as_str())
+        .chain(outcomes.question_scores.iter().map(|q| q.student_id.as_str()))
+        .collect();
+
+    // Look scores up instead of scanning every grade for every alignment
+    let grade_index = index_grades(grades);
+    let mut question_index: HashMap<(&str, &str, &str), &QuestionScore> = HashMap::new();
+    for question in outcomes.question_scores.iter().filter(|q| q.max_score > 0.0) {
+        question_index
+            .entry((question.student_id.as_str(), question.assignment_id.as_str(), question.question_id.as_str()))
+            .or_insert(question);
+    }
+
+    let mut attainment: BTreeMap<String, BTreeMap<String, (f64, usize)>> = BTreeMap::new();
+
+    for objective in &outcomes.objectives {
+        let alignments: Vec<&ObjectiveAlignment> = outcomes.alignments.iter()
+            .filter(|a| a.objective_code == objective.code && a.weight > 0.0)
+            .collect();
+
+        let mut per_student: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+        for student_id in &students {
+            let mut weighted_sum = 0.0;
+            let mut weight_total = 0.0;
+            let mut evidence = 0;
+
+            for alignment in &alignments {
+                let percentage = match &alignment.question_id {
+                    Some(question_id) => question_index
+                        .get(&(*student_id, alignment.assignment_id.as_str(), question_id.as_str()))
+                        .map(|q| (q.score / q.max_score) * 100.0),
+                    None => grade_index
+                        .get(&(*student_id, alignment.assignment_id.as_str()))
+                        .and_then(|list| list.iter().find(|g| g.max_score > 0.0))
+                        .map(|g| (g.score / g.max_score) * 100.0),
+                };
+
+                if let Some(percentage) = percentage {
+                    weighted_sum += alignment.weight * percentage;
+                    weight_total += alignment.weight;
+                    evidence += 1;
+                }
+            }
+
+            if evidence > 0 {
+                per_student.insert(student_id.to_string(), (weighted_sum / weight_total, evidence));
+            }
+        }
+
+        attainment.insert(objective.code.clone(), per_student);
+    }
+
+    attainment
+}
+
+pub(crate) fn compute_objective_mastery(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    outcomes: &OutcomesModel,
+    options: &MasteryOptions,
+) -> ObjectiveMasteryResult {
+    // Flag alignments that point nowhere
+    let mut warnings: Vec<String> = Vec::new();
+    for alignment in &outcomes.alignments {
+        if !outcomes.objectives.iter().any(|o| o.code == alignment.objective_code) {
+            warnings.push(format!(
+                "Alignment for assignment {} references unknown objective {}",
+                alignment.assignment_id, alignment.objective_code
+            ));
+        }
+        if !assignments.is_empty() && !assignments.iter().any(|a| a.id == alignment.assignment_id) {
+            warnings.push(format!(
+                "Objective {} is aligned to unknown assignment {}",
+                alignment.objective_code, alignment.assignment_id
+            ));
+        }
+    }
+    for objective in &outcomes.objectives {
+        if !outcomes.alignments.iter().any(|a| a.objective_code == objective.code) {
+            warnings.push(format!("Objective {} has no aligned assessments", objective.code));
+        }
+    }
+
+    let attainment = objective_attainment(grades, outcomes);
+    let threshold = options.mastery_threshold;
+
+    let objectives: Vec<ClassObjectiveMastery> = outcomes.objectives.iter()
+        .map(|objective| {
+            let per_student = &attainment[&objective.code];
+            let values: Vec<f64> = per_student.values().map(|(value, _)| *value).collect();
+            let mastered = values.iter().filter(|v| **v >= threshold).count();
+            let mastery_rate = if values.is_empty() {
+                0.0
+            } else {
+                mastered as f64 / values.len() as f64
+            };
+
+            ClassObjectiveMastery {
+                objective_code: objective.code.clone(),
+                description: objective.description.clone(),
+                aligned_items: outcomes.alignments.iter()
+                    .filter(|a| a.objective_code == objective.code)
+                    .count(),
+                students_assessed: values.len(),
+                mean_attainment: calculate_mean(&values),
+                mastery_rate,
+                target_met: !values.is_empty() && mastery_rate >= options.class_target,
+            }
+        })
+        .collect();
+
+    let student_ids: BTreeSet<&String> = attainment.values().flat_map(|m| m.keys()).collect();
+    let students: Vec<StudentObjectiveMastery> = student_ids.into_iter()
+        .map(|student_id| {
+            let objectives: Vec<ObjectiveAttainment> = outcomes.objectives.iter()
+                .map(|objective| {
+                    let entry = attainment[&objective.code].get(student_id);
+                    ObjectiveAttainment {
+                        objective_code: objective.code.clone(),
+                        attainment: entry.map(|(value, _)| *value),
+                        mastered: entry.is_some_and(|(value, _)| *value >= threshold),
+                        evidence_count: entry.map(|(_, count)| *count).unwrap_or(0),
+                    }
+                })
+                .collect();
+
+            StudentObjectiveMastery {
+                student_id: student_id.clone(),
+                mastered_count: objectives.iter().filter(|o| o.mastered).count(),
+                objectives,
+            }
+        })
+        .collect();
+
+    ObjectiveMasteryResult {
+        total_students: students.len(),
+        objectives,
+        students,
+        mastery_threshold: threshold,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_objective_mastery_with_question_alignment() {
+        let grade = |student: &str, assignment: &str, score: f64| Grade {
+            student_id: student.to_string(),
+            assignment_id: assignment.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        };
+        let grades = vec![
+            grade("S1", "A1", 90.0), grade("S1", "A2", 60.0),
+            grade("S2", "A1", 50.0), grade("S2", "A2", 80.0),
+        ];
+        let outcomes: OutcomesModel = serde_json::from_str(r#"{
+            "objectives": [
+                {"code": "CLO-1", "description": "Analyze algorithms"},
+                {"code": "CLO-2", "description": "Communicate designs"}
+            ],
+            "alignments": [
+                {"objective_code": "CLO-1", "assignment_id": "A1", "weight": 3},
+                {"objective_code": "CLO-1", "assignment_id": "A2", "question_id": "Q1"},
+                {"objective_code": "CLO-9", "assignment_id": "A2"}
+            ],
+            "question_scores": [
+                {"student_id": "S1", "assignment_id": "A2", "question_id": "Q1", "score": 2, "max_score": 10},
+                {"student_id": "S2", "assignment_id": "A2", "question_id": "Q1", "score": 10, "max_score": 10}
+            ]
+        }"#).unwrap();
+
+        let result = compute_objective_mastery(&grades, &[], &outcomes, &MasteryOptions::default());
+
+        let s1 = &result.students[0].objectives[0];
+        assert_eq!(s1.evidence_count, 2);
+        assert!((s1.attainment.unwrap() - 72.5).abs() < 1e-9);
+        assert!(s1.mastered);
+
+        let clo1 = &result.objectives[0];
+        assert_eq!(clo1.students_assessed, 2);
+        assert_eq!(clo1.mastery_rate, 0.5);
+        assert!(!clo1.target_met);
+        assert_eq!(result.objectives[1].students_assessed, 0);
+        assert_eq!(result.warnings.len(), 2);
+    }
+}
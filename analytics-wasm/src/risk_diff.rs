@@ -0,0 +1,220 @@
+// ============================================================================
+// Risk Snapshot Diff (what changed since the caller's previous run)
+// ============================================================================
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::risk_model::RiskModel;
+use crate::{compute_early_intervention, Assignment, EarlyInterventionResult, Grade, RiskAssessment};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RiskDiffOptions {
+    pub score_increase_threshold: f64,   // Risk score rise (points) worth reporting
+    pub model: Option<RiskModel>,        // Score with a trained model, as the snapshot was
+}
+
+impl Default for RiskDiffOptions {
+    fn default() -> Self {
+        RiskDiffOptions {
+            score_increase_threshold: 20.0,
+            model: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RiskChange {
+    pub student_id: String,
+    pub previous_level: Option<String>,   // None for students new to the gradebook
+    pub current_level: String,
+    pub previous_score: Option<f64>,
+    pub current_score: f64,
+    pub score_change: Option<f64>,
+    pub new_factors: Vec<String>,
+    pub resolved_factors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RiskDiffResult {
+    pub newly_high_risk: Vec<String>,
+    pub score_increases: Vec<String>,     // Rose by more than the threshold
+    pub recovered: Vec<String>,           // High or medium before, low now
+    pub changes: Vec<RiskChange>,         // Every student whose level, score or factors moved
+    pub departed_students: Vec<String>,   // In the snapshot, no longer graded
+    pub current: EarlyInterventionResult, // Store as the next snapshot
+}
+
+#[wasm_bindgen]
+pub fn diff_early_intervention(
+    grades_json: &str,
+    assignments_json: &str,
+    previous_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let previous: EarlyInterventionResult = serde_json::from_str(previous_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse previous snapshot: {}", e)))?;
+
+    let options: RiskDiffOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    if let Some(model) = &options.model {
+        model.validate().map_err(|e| JsValue::from_str(&format!("Invalid model: {}", e)))?;
+    }
+
+    let current = compute_early_intervention(&grades, &assignments, options.model.as_ref());
+    let result = diff_risk_snapshots(&previous, current, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+fn assessments_by_student(result: &EarlyInterventionResult) -> BTreeMap<&str, &RiskAssessment> {
+    result.high_risk.iter()
+        .chain(&result.medium_risk)
+        .chain(&result.low_risk)
+        .map(|a| (a.student_id.as_str(), a))
+        .collect()
+}
+
+// Factors embed their numbers ("Low average score: 55.0%"), so compare by kind
+fn factor_kind(factor: &str) -> &str {
+    factor.split(':').next().unwrap_or(factor).trim()
+}
+
+pub(crate) fn diff_risk_snapshots(
+    previous: &EarlyInterventionResult,
+    current: EarlyInterventionResult,
+    options: &RiskDiffOptions,
+) -> RiskDiffResult {
+    let before = assessments_by_student(previous);
+    let after = assessments_by_student(&current);
+
+    let mut newly_high_risk = Vec::new();
+    let mut score_increases = Vec::new();
+    let mut recovered = Vec::new();
+    let mut changes = Vec::new();
+
+    for (student_id, now) in &after {
+        let then = before.get(student_id);
+        let score_change = then.map(|t| now.risk_score - t.risk_score);
+
+        let kinds = |a: &RiskAssessment| -> Vec<String> {
+            a.factors.iter().map(|f| factor_kind(f).to_string()).collect()
+        };
+        let now_kinds = kinds(now);
+        let then_kinds = then.map(|t| kinds(t)).unwrap_or_default();
+        let new_factors: Vec<String> = now.factors.iter()
+            .filter(|f| !then_kinds.iter().any(|k| k == factor_kind(f)))
+            .cloned()
+            .collect();
+        let resolved_factors: Vec<String> = then.map(|t| {
+            t.factors.iter()
+                .filter(|f| !now_kinds.iter().any(|k| k == factor_kind(f)))
+                .cloned()
+                .collect()
+        }).unwrap_or_default();
+
+        let previous_level = then.map(|t| t.risk_level.clone());
+        if now.risk_level == "high" && previous_level.as_deref() != Some("high") {
+            newly_high_risk.push(student_id.to_string());
+        }
+        if score_change.is_some_and(|c| c > options.score_increase_threshold) {
+            score_increases.push(student_id.to_string());
+        }
+        if now.risk_level == "low" && matches!(previous_level.as_deref(), Some("high") | Some("medium")) {
+            recovered.push(student_id.to_string());
+        }
+
+        let moved = previous_level.as_deref() != Some(now.risk_level.as_str())
+            || score_change.is_some_and(|c| c != 0.0)
+            || !new_factors.is_empty()
+            || !resolved_factors.is_empty();
+        if moved {
+            changes.push(RiskChange {
+                student_id: student_id.to_string(),
+                previous_level,
+                current_level: now.risk_level.clone(),
+                previous_score: then.map(|t| t.risk_score),
+                current_score: now.risk_score,
+                score_change,
+                new_factors,
+                resolved_factors,
+            });
+        }
+    }
+
+    let departed_students: Vec<String> = before.keys()
+        .filter(|id| !after.contains_key(*id))
+        .map(|id| id.to_string())
+        .collect();
+
+    RiskDiffResult {
+        newly_high_risk,
+        score_increases,
+        recovered,
+        changes,
+        departed_students,
+        current,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_against_older_snapshot() {
+        // Snapshot written before the shrinkage fields existed
+        let previous: EarlyInterventionResult = serde_json::from_str(r#"{
+            "high_risk": [
+                {"student_id": "S2", "risk_level": "high", "risk_score": 70,
+                 "factors": ["Low average score: 52.0%", "High missing submission rate: 40%"], "recommendations": []}
+            ],
+            "medium_risk": [],
+            "low_risk": [
+                {"student_id": "S1", "risk_level": "low", "risk_score": 0, "factors": [], "recommendations": []},
+                {"student_id": "S9", "risk_level": "low", "risk_score": 0, "factors": [], "recommendations": []}
+            ],
+            "total_students": 3
+        }"#).unwrap();
+
+        let grade = |student: &str, assignment: &str, score: f64| Grade {
+            student_id: student.to_string(),
+            assignment_id: assignment.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        };
+        let mut grades = Vec::new();
+        for (i, (s1, s2)) in [(0.0, 90.0), (0.0, 88.0), (40.0, 92.0), (0.0, 85.0), (0.0, 91.0)].iter().enumerate() {
+            let assignment = format!("A{}", i + 1);
+            grades.push(grade("S1", &assignment, *s1));
+            grades.push(grade("S2", &assignment, *s2));
+        }
+
+        let current = compute_early_intervention(&grades, &[], None);
+        let result = diff_risk_snapshots(&previous, current, &RiskDiffOptions::default());
+
+        assert_eq!(result.newly_high_risk, vec!["S1".to_string()]);
+        assert_eq!(result.score_increases, vec!["S1".to_string()]);
+        assert_eq!(result.recovered, vec!["S2".to_string()]);
+        assert_eq!(result.departed_students, vec!["S9".to_string()]);
+
+        let s2 = result.changes.iter().find(|c| c.student_id == "S2").unwrap();
+        assert_eq!(s2.resolved_factors.len(), 2);
+        let s1 = result.changes.iter().find(|c| c.student_id == "S1").unwrap();
+        assert!(s1.new_factors.iter().any(|f| f.starts_with("High missing submission rate")));
+    }
+}
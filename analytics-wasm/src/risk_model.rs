@@ -0,0 +1,570 @@
+// ============================================================================
+// Predictive Risk Model (L2 logistic regression on past course outcomes)
+// ============================================================================
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::assignment_timeline;
+use crate::random::SeededRng;
+use crate::shrinkage::{self, ShrinkagePrior};
+use crate::{calculate_mean, calculate_recent_average, calculate_std_deviation, Assignment, Grade, RiskAssessment};
+
+// Features in model order; the same factors the fixed point system looks at
+pub(crate) const FEATURE_NAMES: &[&str] = &["average", "recent_decline", "missing_rate", "low_score_share"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StudentOutcome {
+    pub student_id: String,
+    #[serde(default)]
+    pub letter: Option<String>,    // Final letter, e.g. "B+", "F", "W"
+    #[serde(default)]
+    pub passed: Option<bool>,      // Wins over letter when both are given
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoricalCourse {
+    #[serde(default)]
+    pub course_id: Option<String>,
+    pub grades: Vec<Grade>,
+    #[serde(default)]
+    pub assignments: Vec<Assignment>,
+    pub outcomes: Vec<StudentOutcome>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RiskModelOptions {
+    pub l2_penalty: f64,
+    pub iterations: usize,
+    pub learning_rate: f64,
+    pub folds: usize,
+    pub seed: u64,
+    pub calibration_bins: usize,
+    pub failing_letters: Vec<String>,   // Letters that count as a bad outcome
+    pub observe_fraction: f64,          // Share (0-1] of each past semester's assignments to train on
+    pub high_threshold: f64,            // Probability for "high" risk
+    pub medium_threshold: f64,          // Probability for "medium" risk
+}
+
+impl Default for RiskModelOptions {
+    fn default() -> Self {
+        RiskModelOptions {
+            l2_penalty: 1.0,
+            iterations: 1000,
+            learning_rate: 0.5,
+            folds: 5,
+            seed: 42,
+            calibration_bins: 5,
+            failing_letters: vec!["D".to_string(), "F".to_string(), "W".to_string()],
+            observe_fraction: 0.5,
+            high_threshold: 0.5,
+            medium_threshold: 0.25,
+        }
+    }
+}
+
+// Compact, portable model: standardisation plus coefficients
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RiskModel {
+    pub version: u32,
+    pub features: Vec<String>,
+    pub means: Vec<f64>,
+    pub scales: Vec<f64>,
+    pub weights: Vec<f64>,              // Per standardised feature
+    pub intercept: f64,
+    pub high_threshold: f64,
+    pub medium_threshold: f64,
+    pub trained_on: usize,
+    pub base_rate: f64,                 // Share of bad outcomes in the training data
+    #[serde(default = "full_semester")]
+    pub observe_fraction: f64,          // Share of each semester trained on; current students are scored on the same share
+}
+
+// Models saved before the training window was recorded saw whole semesters
+fn full_semester() -> f64 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CalibrationBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_predicted: f64,
+    pub observed_rate: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModelEvaluation {
+    pub cross_validated_auc: Option<f64>,   // Pooled out-of-fold predictions
+    pub fold_aucs: Vec<Option<f64>>,
+    pub brier_score: f64,
+    pub calibration: Vec<CalibrationBin>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RiskModelTraining {
+    pub model: RiskModel,
+    pub evaluation: ModelEvaluation,
+    pub students: usize,
+    pub positives: usize,
+    pub warnings: Vec<String>,
+}
+
+#[wasm_bindgen]
+pub fn train_risk_model(history_json: &str, options_json: &str) -> Result<String, JsValue> {
+    // Parse input data
+    let history: Vec<HistoricalCourse> = serde_json::from_str(history_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse history: {}", e)))?;
+
+    let options: RiskModelOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = fit_risk_model(&history, &options)
+        .map_err(|e| JsValue::from_str(&format!("Failed to train model: {}", e)))?;
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Feature vector for one student's grades, matching FEATURE_NAMES
+pub(crate) fn student_features(grades: &[&Grade], average_prior: Option<&ShrinkagePrior>) -> Vec<f64> {
+    let scores: Vec<f64> = grades.iter().map(|g| (g.score / g.max_score) * 100.0).collect();
+    let average = calculate_mean(&scores);
+    let shrunk_average = match average_prior {
+        Some(prior) => prior.shrink_average(average, scores.len()).shrunk,
+        None => average,
+    };
+    let recent_decline = if grades.len() >= 3 {
+        (average - calculate_recent_average(grades, 3)).max(0.0)
+    } else {
+        0.0
+    };
+    let share = |count: usize| {
+        if scores.is_empty() { 0.0 } else { count as f64 / scores.len() as f64 * 100.0 }
+    };
+
+    vec![
+        shrunk_average,
+        recent_decline,
+        share(grades.iter().filter(|g| g.score == 0.0).count()),
+        share(scores.iter().filter(|s| **s < 60.0).count()),
+    ]
+}
+
+fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+impl RiskModel {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let k = self.features.len();
+        if self.means.len() != k || self.scales.len() != k || self.weights.len() != k {
+            return Err("feature, mean, scale and weight lists differ in length".to_string());
+        }
+        if let Some(unknown) = self.features.iter().find(|f| !FEATURE_NAMES.contains(&f.as_str())) {
+            return Err(format!("unknown feature \"{}\"", unknown));
+        }
+        let parameters = self.means.iter().chain(&self.scales).chain(&self.weights).chain([&self.intercept]);
+        if parameters.copied().any(|v| !v.is_finite()) {
+            return Err("means, scales, weights and intercept must be finite numbers".to_string());
+        }
+        if !self.high_threshold.is_finite() || !self.medium_threshold.is_finite() {
+            return Err("risk thresholds must be finite numbers".to_string());
+        }
+        Ok(())
+    }
+
+    // Log-odds contribution of each feature
+    fn contributions(&self, features: &[f64]) -> Vec<f64> {
+        self.features.iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let value = FEATURE_NAMES.iter().position(|f| f == name).map(|j| features[j]).unwrap_or(0.0);
+                let scale = if self.scales[i] > 0.0 { self.scales[i] } else { 1.0 };
+                self.weights[i] * (value - self.means[i]) / scale
+            })
+            .collect()
+    }
+
+    pub(crate) fn predict(&self, features: &[f64]) -> f64 {
+        sigmoid(self.intercept + self.contributions(features).iter().sum::<f64>())
+    }
+
+    // Drop-in replacement for the fixed-weight assessment
+    pub(crate) fn assess(
+        &self,
+        student_id: &str,
+        grades: &[&Grade],
+        average_prior: Option<&ShrinkagePrior>,
+    ) -> RiskAssessment {
+        let grades: Vec<&Grade> = grades.iter().copied().filter(|g| g.max_score > 0.0).collect();
+        let grades = grades.as_slice();
+        let features = student_features(grades, average_prior);
+        let scores: Vec<f64> = grades.iter().map(|g| (g.score / g.max_score) * 100.0).collect();
+        let raw_average = calculate_mean(&scores);
+        let reliability = average_prior
+            .map(|prior| prior.shrink_average(raw_average, scores.len()).reliability)
+            .unwrap_or(1.0);
+
+        let probability = if grades.is_empty() { self.base_rate } else { self.predict(&features) };
+        let risk_level = if probability >= self.high_threshold {
+            "high"
+        } else if probability >= self.medium_threshold {
+            "medium"
+        } else {
+            "low"
+        };
+
+        // Explain with the features pushing risk up the most
+        let mut drivers: Vec<(&str, f64, f64)> = self.features.iter()
+            .zip(self.contributions(&features))
+            .filter(|(_, contribution)| *contribution > 0.1)
+            .filter_map(|(name, contribution)| {
+                let j = FEATURE_NAMES.iter().position(|f| f == name)?;
+                Some((FEATURE_NAMES[j], features[j], contribution))
+            })
+            .collect();
+        drivers.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+
+        let mut factors = vec![format!("Model risk probability: {:.0}%", probability * 100.0)];
+        let mut recommendations = Vec::new();
+        for (name, value, _) in &drivers {
+            let (factor, recommendation) = match *name {
+                "average" => (format!("Low average score: {:.1}%", value), "Schedule one-on-one meeting"),
+                "recent_decline" => (format!("Declining performance trend: {:.1} points", value), "Identify struggling topics"),
+                "missing_rate" => (format!("Missing submission rate: {:.0}%", value), "Check for personal/technical issues"),
+                _ => (format!("Share of scores below 60%: {:.0}%", value), "Review foundational material"),
+            };
+            factors.push(factor);
+            recommendations.push(recommendation.to_string());
+        }
+        if recommendations.is_empty() {
+            recommendations.push("Continue monitoring progress".to_string());
+        }
+
+        RiskAssessment {
+            student_id: student_id.to_string(),
+            risk_level: risk_level.to_string(),
+            risk_score: probability * 100.0,
+            factors,
+            recommendations,
+            grade_count: grades.len(),
+            raw_average,
+            shrunk_average: features[0],
+            data_sufficiency: shrinkage::data_sufficiency(grades.len(), reliability),
+        }
+    }
+}
+
+// Outcome label: true for a bad outcome (fail, D/F/W by default)
+fn outcome_label(outcome: &StudentOutcome, failing_letters: &[String]) -> Option<bool> {
+    if let Some(passed) = outcome.passed {
+        return Some(!passed);
+    }
+    let letter = outcome.letter.as_deref()?.trim().chars().next()?.to_ascii_uppercase();
+    Some(failing_letters.iter().any(|l| l.trim().eq_ignore_ascii_case(&letter.to_string())))
+}
+
+// Assignment ids in the first `fraction` of a course's timeline
+pub(crate) fn observed_assignments(grades: &[Grade], assignments: &[Assignment], fraction: f64) -> HashSet<String> {
+    let timeline = assignment_timeline(grades, assignments);
+    let observed = ((timeline.len() as f64 * fraction.clamp(0.0, 1.0)).ceil() as usize).max(1);
+    timeline.into_iter().take(observed).map(|(id, _)| id).collect()
+}
+
+// Labelled feature rows across all past courses
+fn training_rows(
+    history: &[HistoricalCourse],
+    options: &RiskModelOptions,
+    warnings: &mut Vec<String>,
+) -> Vec<(Vec<f64>, bool)> {
+    let mut rows = Vec::new();
+    for (index, course) in history.iter().enumerate() {
+        let course_name = course.course_id.clone().unwrap_or_else(|| format!("course {}", index + 1));
+
+        // Only the early part of the semester, when predictions are made
+        let observed_ids = observed_assignments(&course.grades, &course.assignments, options.observe_fraction);
+
+        let mut by_student: BTreeMap<&str, Vec<&Grade>> = BTreeMap::new();
+        for grade in course.grades.iter().filter(|g| g.max_score > 0.0) {
+            if observed_ids.contains(&grade.assignment_id) {
+                by_student.entry(grade.student_id.as_str()).or_default().push(grade);
+            }
+        }
+        let score_lists: Vec<Vec<f64>> = by_student.values()
+            .map(|list| list.iter().map(|g| (g.score / g.max_score) * 100.0).collect())
+            .collect();
+        let prior = ShrinkagePrior::for_averages(&score_lists);
+
+        let outcomes: HashMap<&str, &StudentOutcome> = course.outcomes.iter()
+            .map(|o| (o.student_id.as_str(), o))
+            .collect();
+        let mut unlabelled = 0;
+        for (student_id, grades) in &by_student {
+            match outcomes.get(student_id).and_then(|o| outcome_label(o, &options.failing_letters)) {
+                Some(label) => rows.push((student_features(grades, prior.as_ref()), label)),
+                None => unlabelled += 1,
+            }
+        }
+        if unlabelled > 0 {
+            warnings.push(format!("{}: {} students without a usable outcome were skipped", course_name, unlabelled));
+        }
+    }
+    rows
+}
+
+// Fitted L2 logistic regression on standardised inputs
+pub(crate) struct LogisticFit {
+    pub means: Vec<f64>,
+    pub scales: Vec<f64>,
+    pub weights: Vec<f64>,
+    pub intercept: f64,
+}
+
+impl LogisticFit {
+    pub(crate) fn predict(&self, x: &[f64]) -> f64 {
+        let z: f64 = (0..self.weights.len())
+            .map(|j| self.weights[j] * (x[j] - self.means[j]) / self.scales[j])
+            .sum();
+        sigmoid(self.intercept + z)
+    }
+}
+
+// Batch gradient descent on standardised features; intercept is not penalised
+pub(crate) fn fit_l2_logistic(
+    rows: &[(Vec<f64>, bool)],
+    l2_penalty: f64,
+    iterations: usize,
+    learning_rate: f64,
+) -> LogisticFit {
+    let k = rows.first().map(|r| r.0.len()).unwrap_or(0);
+    let n = rows.len().max(1) as f64;
+
+    let means: Vec<f64> = (0..k).map(|j| calculate_mean(&rows.iter().map(|r| r.0[j]).collect::<Vec<_>>())).collect();
+    let scales: Vec<f64> = (0..k)
+        .map(|j| {
+            let column: Vec<f64> = rows.iter().map(|r| r.0[j]).collect();
+            let std = calculate_std_deviation(&column, means[j]);
+            if std > 0.0 { std } else { 1.0 }
+        })
+        .collect();
+    let standardized: Vec<Vec<f64>> = rows.iter()
+        .map(|(x, _)| (0..k).map(|j| (x[j] - means[j]) / scales[j]).collect())
+        .collect();
+
+    let base_rate = (rows.iter().filter(|r| r.1).count() as f64 / n).clamp(1e-3, 1.0 - 1e-3);
+    let mut weights = vec![0.0; k];
+    let mut intercept = (base_rate / (1.0 - base_rate)).ln();
+
+    for _ in 0..iterations {
+        let mut gradient = vec![0.0; k];
+        let mut gradient_intercept = 0.0;
+        for (x, (_, label)) in standardized.iter().zip(rows) {
+            let z = intercept + x.iter().zip(&weights).map(|(a, w)| a * w).sum::<f64>();
+            let error = sigmoid(z) - if *label { 1.0 } else { 0.0 };
+            for j in 0..k {
+                gradient[j] += error * x[j];
+            }
+            gradient_intercept += error;
+        }
+        for j in 0..k {
+            weights[j] -= learning_rate * (gradient[j] + l2_penalty * weights[j]) / n;
+        }
+        intercept -= learning_rate * gradient_intercept / n;
+    }
+
+    LogisticFit { means, scales, weights, intercept }
+}
+
+fn fit_logistic(rows: &[(Vec<f64>, bool)], options: &RiskModelOptions) -> RiskModel {
+    let fit = fit_l2_logistic(rows, options.l2_penalty, options.iterations, options.learning_rate);
+
+    RiskModel {
+        version: 1,
+        features: FEATURE_NAMES.iter().map(|f| f.to_string()).collect(),
+        means: fit.means,
+        scales: fit.scales,
+        weights: fit.weights,
+        intercept: fit.intercept,
+        high_threshold: options.high_threshold,
+        medium_threshold: options.medium_threshold,
+        trained_on: rows.len(),
+        observe_fraction: options.observe_fraction.clamp(0.0, 1.0),
+        base_rate: rows.iter().filter(|r| r.1).count() as f64 / rows.len().max(1) as f64,
+    }
+}
+
+// Area under the ROC curve via the rank-sum statistic (ties count half)
+pub(crate) fn roc_auc(predictions: &[(f64, bool)]) -> Option<f64> {
+    let positives: Vec<f64> = predictions.iter().filter(|p| p.1).map(|p| p.0).collect();
+    let negatives: Vec<f64> = predictions.iter().filter(|p| !p.1).map(|p| p.0).collect();
+    if positives.is_empty() || negatives.is_empty() {
+        return None;
+    }
+
+    let mut wins = 0.0;
+    for p in &positives {
+        for q in &negatives {
+            wins += if p > q { 1.0 } else if p == q { 0.5 } else { 0.0 };
+        }
+    }
+    Some(wins / (positives.len() * negatives.len()) as f64)
+}
+
+fn calibration_table(predictions: &[(f64, bool)], bins: usize) -> Vec<CalibrationBin> {
+    let bins = bins.max(1);
+    (0..bins)
+        .map(|b| {
+            let lower = b as f64 / bins as f64;
+            let upper = (b + 1) as f64 / bins as f64;
+            let members: Vec<&(f64, bool)> = predictions.iter()
+                .filter(|(p, _)| *p >= lower && (*p < upper || (b + 1 == bins && *p <= upper)))
+                .collect();
+            let count = members.len();
+            CalibrationBin {
+                lower,
+                upper,
+                count,
+                mean_predicted: calculate_mean(&members.iter().map(|m| m.0).collect::<Vec<_>>()),
+                observed_rate: if count > 0 {
+                    members.iter().filter(|m| m.1).count() as f64 / count as f64
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn fit_risk_model(
+    history: &[HistoricalCourse],
+    options: &RiskModelOptions,
+) -> Result<RiskModelTraining, String> {
+    let mut warnings = Vec::new();
+    let rows = training_rows(history, options, &mut warnings);
+
+    let positives = rows.iter().filter(|r| r.1).count();
+    if positives == 0 || positives == rows.len() {
+        return Err(format!(
+            "need both passing and failing outcomes to train ({} students, {} failing)",
+            rows.len(),
+            positives
+        ));
+    }
+
+    // Out-of-fold predictions from a seeded shuffle
+    let folds = options.folds.clamp(2, rows.len());
+    let mut order: Vec<usize> = (0..rows.len()).collect();
+    let mut rng = SeededRng::new(options.seed);
+    for i in (1..order.len()).rev() {
+        let j = rng.next_index(i + 1);
+        order.swap(i, j);
+    }
+    let mut fold_of = vec![0; rows.len()];
+    for (position, index) in order.iter().enumerate() {
+        fold_of[*index] = position % folds;
+    }
+
+    let mut out_of_fold: Vec<(f64, bool)> = Vec::new();
+    let mut fold_aucs = Vec::new();
+    for fold in 0..folds {
+        let train: Vec<(Vec<f64>, bool)> = rows.iter()
+            .enumerate()
+            .filter(|(i, _)| fold_of[*i] != fold)
+            .map(|(_, r)| r.clone())
+            .collect();
+        let model = fit_logistic(&train, options);
+        let predictions: Vec<(f64, bool)> = rows.iter()
+            .enumerate()
+            .filter(|(i, _)| fold_of[*i] == fold)
+            .map(|(_, (x, label))| (model.predict(x), *label))
+            .collect();
+        fold_aucs.push(roc_auc(&predictions));
+        out_of_fold.extend(predictions);
+    }
+
+    let brier_score = calculate_mean(
+        &out_of_fold.iter()
+            .map(|(p, label)| (p - if *label { 1.0 } else { 0.0 }).powi(2))
+            .collect::<Vec<_>>(),
+    );
+
+    Ok(RiskModelTraining {
+        model: fit_logistic(&rows, options),
+        evaluation: ModelEvaluation {
+            cross_validated_auc: roc_auc(&out_of_fold),
+            fold_aucs,
+            brier_score,
+            calibration: calibration_table(&out_of_fold, options.calibration_bins),
+        },
+        students: rows.len(),
+        positives,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trained_model_separates_outcomes() {
+        // Averages below ~65 failed in the past semester
+        let mut grades = Vec::new();
+        let mut outcomes = Vec::new();
+        for s in 0..40 {
+            let base = 35.0 + s as f64 * 1.5;
+            for a in 0..4 {
+                grades.push(Grade {
+                    student_id: format!("S{}", s),
+                    assignment_id: format!("A{}", a),
+                    score: if s % 7 == 0 && a == 3 { 0.0 } else { base + a as f64 },
+                    max_score: 100.0,
+                    submitted_at: None,
+                    due_date: None,
+                });
+            }
+            outcomes.push(StudentOutcome {
+                student_id: format!("S{}", s),
+                letter: Some(if base < 65.0 { "F" } else { "B" }.to_string()),
+                passed: None,
+            });
+        }
+        let history = vec![HistoricalCourse { course_id: None, grades: grades.clone(), assignments: vec![], outcomes }];
+
+        let training = fit_risk_model(&history, &RiskModelOptions::default()).unwrap();
+
+        assert_eq!(training.students, 40);
+        assert_eq!(training.positives, 20);
+        assert!(training.evaluation.cross_validated_auc.unwrap() > 0.9);
+        assert!(training.model.weights[0] < 0.0);
+
+        // Round-trips through JSON and plugs into Early Intervention
+        let model: RiskModel = serde_json::from_str(&serde_json::to_string(&training.model).unwrap()).unwrap();
+        let result = crate::compute_early_intervention(&grades, &[], Some(&model));
+        assert!(result.high_risk.iter().any(|a| a.student_id == "S0"));
+        assert!(result.low_risk.iter().any(|a| a.student_id == "S39"));
+        assert!(result.high_risk[0].factors[0].starts_with("Model risk probability"));
+        assert_eq!(model.observe_fraction, 0.5);
+
+        // Trained on the first half, so late zeros are outside what it scores
+        let mut late = grades.clone();
+        for grade in late.iter_mut().filter(|g| g.student_id == "S39" && g.assignment_id.as_str() >= "A2") {
+            grade.score = 0.0;
+        }
+        let result = crate::compute_early_intervention(&late, &[], Some(&model));
+        let top = result.low_risk.iter().find(|a| a.student_id == "S39").unwrap();
+        assert_eq!(top.grade_count, 2);
+
+        let mut broken = model.clone();
+        broken.weights[1] = f64::NAN;
+        assert!(broken.validate().is_err());
+        broken = model;
+        broken.high_threshold = f64::INFINITY;
+        assert!(broken.validate().is_err());
+    }
+}
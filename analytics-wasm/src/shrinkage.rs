@@ -0,0 +1,190 @@
+// ============================================================================
+// Empirical-Bayes Shrinkage for sparse per-student estimates
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+
+use crate::calculate_mean;
+
+// Class-level prior estimated from every student's raw estimate
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShrinkagePrior {
+    pub mean: f64,              // Class mean of the raw estimates
+    pub between_variance: f64,  // True spread between students (tau^2)
+    pub noise_variance: f64,    // Per-observation noise (within-student or residual)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShrunkEstimate {
+    pub raw: f64,
+    pub shrunk: f64,
+    pub reliability: f64,  // 0-1, weight given to the student's own data
+}
+
+impl ShrinkagePrior {
+    // Method-of-moments fit from (estimate, sampling variance) pairs.
+    // Returns None when there are too few students to estimate a spread.
+    pub fn from_estimates(estimates: &[(f64, f64)], noise_variance: f64) -> Option<ShrinkagePrior> {
+        if estimates.len() < 2 {
+            return None;
+        }
+
+        let values: Vec<f64> = estimates.iter().map(|(value, _)| *value).collect();
+        let mean = calculate_mean(&values);
+        let observed_variance = values.iter()
+            .map(|v| (v - mean).powi(2))
+            .sum::<f64>() / (values.len() - 1) as f64;
+        let noise = estimates.iter().map(|(_, var)| *var).sum::<f64>() / estimates.len() as f64;
+
+        Some(ShrinkagePrior {
+            mean,
+            between_variance: (observed_variance - noise).max(0.0),
+            noise_variance,
+        })
+    }
+
+    // Fit a prior for student averages from each student's percentage scores
+    pub fn for_averages(students: &[Vec<f64>]) -> Option<ShrinkagePrior> {
+        let within = pooled_within_variance(students);
+        let estimates: Vec<(f64, f64)> = students.iter()
+            .filter(|scores| !scores.is_empty())
+            .map(|scores| (calculate_mean(scores), within / scores.len() as f64))
+            .collect();
+        Self::from_estimates(&estimates, within)
+    }
+
+    // Shrink a student's average of `count` scores
+    pub fn shrink_average(&self, raw: f64, count: usize) -> ShrunkEstimate {
+        self.shrink(raw, self.noise_variance / count.max(1) as f64)
+    }
+
+    // Pull a raw estimate toward the class mean in proportion to its noise
+    pub fn shrink(&self, raw: f64, sampling_variance: f64) -> ShrunkEstimate {
+        let total = self.between_variance + sampling_variance;
+        let reliability = if total > 0.0 {
+            self.between_variance / total
+        } else {
+            1.0
+        };
+
+        ShrunkEstimate {
+            raw,
+            shrunk: self.mean + reliability * (raw - self.mean),
+            reliability,
+        }
+    }
+}
+
+// Pooled within-student variance of scores (students with 2+ scores)
+pub fn pooled_within_variance(students: &[Vec<f64>]) -> f64 {
+    let mut sum_squares = 0.0;
+    let mut degrees_of_freedom = 0usize;
+
+    for scores in students.iter().filter(|s| s.len() >= 2) {
+        let mean = calculate_mean(scores);
+        sum_squares += scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>();
+        degrees_of_freedom += scores.len() - 1;
+    }
+
+    if degrees_of_freedom == 0 {
+        0.0
+    } else {
+        sum_squares / degrees_of_freedom as f64
+    }
+}
+
+// Ordinary least-squares slope over evenly spaced points, with the sum of
+// squared x deviations and the residual sum of squares
+pub fn slope_with_residuals(scores: &[f64]) -> (f64, f64, f64) {
+    let n = scores.len();
+    if n < 2 {
+        return (0.0, 0.0, 0.0);
+    }
+
+    let x_mean = (n - 1) as f64 / 2.0;
+    let y_mean = calculate_mean(scores);
+    let mut sxx = 0.0;
+    let mut sxy = 0.0;
+    for (i, y) in scores.iter().enumerate() {
+        sxx += (i as f64 - x_mean).powi(2);
+        sxy += (i as f64 - x_mean) * (y - y_mean);
+    }
+
+    let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+    let residual_ss: f64 = scores.iter()
+        .enumerate()
+        .map(|(i, y)| (y - (y_mean + slope * (i as f64 - x_mean))).powi(2))
+        .sum();
+
+    (slope, sxx, residual_ss)
+}
+
+// Prior for per-student slopes; the pooled residual variance is used as
+// every student's noise level
+pub fn slope_prior(students: &[Vec<f64>]) -> Option<ShrinkagePrior> {
+    let fits: Vec<(f64, f64, f64, usize)> = students.iter()
+        .filter(|s| s.len() >= 2)
+        .map(|s| {
+            let (slope, sxx, rss) = slope_with_residuals(s);
+            (slope, sxx, rss, s.len())
+        })
+        .collect();
+
+    // Two-point fits have no residual degrees of freedom, so pool the rest
+    let residual_df: usize = fits.iter().map(|f| f.3.saturating_sub(2)).sum();
+    let residual_variance = if residual_df > 0 {
+        fits.iter().map(|f| f.2).sum::<f64>() / residual_df as f64
+    } else {
+        pooled_within_variance(students)
+    };
+
+    let estimates: Vec<(f64, f64)> = fits.iter()
+        .filter(|f| f.1 > 0.0)
+        .map(|f| (f.0, residual_variance / f.1))
+        .collect();
+
+    ShrinkagePrior::from_estimates(&estimates, residual_variance)
+}
+
+// Label how much a student's own data can be trusted
+pub fn data_sufficiency(grade_count: usize, reliability: f64) -> String {
+    if grade_count < 3 || reliability < 0.4 {
+        "insufficient".to_string()
+    } else if grade_count < 5 || reliability < 0.7 {
+        "limited".to_string()
+    } else {
+        "sufficient".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_students_shrink_more() {
+        let students = vec![
+            vec![40.0],
+            vec![40.0, 42.0, 38.0, 41.0, 39.0, 40.0, 43.0, 37.0, 40.0, 40.0],
+            vec![80.0, 85.0, 78.0, 90.0],
+            vec![70.0, 75.0, 72.0],
+            vec![88.0, 92.0, 85.0, 95.0, 90.0],
+        ];
+        let prior = ShrinkagePrior::for_averages(&students).unwrap();
+
+        let sparse = prior.shrink_average(40.0, 1);
+        let dense = prior.shrink_average(40.0, 10);
+
+        assert!(sparse.shrunk > dense.shrunk);
+        assert!(sparse.reliability < dense.reliability);
+        assert_eq!(sparse.raw, 40.0);
+    }
+
+    #[test]
+    fn test_slope_with_residuals() {
+        let (slope, sxx, rss) = slope_with_residuals(&[60.0, 70.0, 80.0]);
+        assert!((slope - 10.0).abs() < 1e-9);
+        assert!((sxx - 2.0).abs() < 1e-9);
+        assert!(rss.abs() < 1e-9);
+    }
+}
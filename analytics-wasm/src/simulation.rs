@@ -0,0 +1,404 @@
+// ============================================================================
+// End-of-Term Grade Forecast (Monte Carlo simulation)
+// ============================================================================
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::assignment_timeline;
+use crate::random::SeededRng;
+use crate::submission::resolve_as_of;
+use crate::{
+    calculate_mean, calculate_quantile, calculate_std_deviation, compute_learning_progression,
+    letter_grade, Assignment, Grade, ProgressionOptions, LETTERS,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SimulationOptions {
+    pub simulations: usize,
+    pub seed: u64,
+    pub shrinkage_strength: f64,                 // Pseudo-observations of the class distribution
+    pub assignment_weights: HashMap<String, f64>, // Overrides max_score as the grade weight
+    pub as_of: Option<String>,                   // Ungraded work due by then scores 0; defaults to the latest submission
+    pub progression: ProgressionOptions,
+}
+
+impl Default for SimulationOptions {
+    fn default() -> Self {
+        SimulationOptions {
+            simulations: 2000,
+            seed: 42,
+            shrinkage_strength: 3.0,
+            assignment_weights: HashMap::new(),
+            as_of: None,
+            progression: ProgressionOptions::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LetterBand {
+    pub letter: String,
+    pub expected_count: f64,
+    pub lower: f64,            // 5th percentile of simulated counts
+    pub upper: f64,            // 95th percentile of simulated counts
+    pub expected_share: f64,   // 0-1
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentOutlook {
+    pub student_id: String,
+    pub current_percentage: f64,   // Weighted over graded and past-due work
+    pub expected_final: f64,
+    pub final_lower: f64,          // 5th percentile
+    pub final_upper: f64,          // 95th percentile
+    pub most_likely_letter: String,
+    pub probability_d_or_f: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GradeSimulationResult {
+    pub simulations: usize,
+    pub seed: u64,
+    pub remaining_assignments: usize,   // Not yet due and not graded for anyone
+    pub letter_distribution: Vec<LetterBand>,
+    // Withdrawals are not visible in the gradebook, so this is the D/F share only
+    pub d_or_f: LetterBand,
+    pub student_outlooks: Vec<StudentOutlook>,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn simulate_grade_distribution(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: SimulationOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = run_grade_simulation(&grades, &assignments, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Per-student sampling distribution for remaining work
+struct StudentModel {
+    student_id: String,
+    earned_weight: f64,     // Sum of weight * fraction earned on graded work
+    graded_weight: f64,     // Includes past-due missing work, earned at 0
+    remaining_weight: f64,
+    remaining: Vec<f64>,    // Weights of ungraded assignments not yet due
+    mean: f64,              // Shrunk location (0-100)
+    std_dev: f64,           // Shrunk spread (0-100)
+}
+
+pub(crate) fn run_grade_simulation(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &SimulationOptions,
+) -> GradeSimulationResult {
+    let weight_of = |assignment_id: &str, max_score: f64| -> f64 {
+        options.assignment_weights
+            .get(assignment_id)
+            .copied()
+            .unwrap_or(max_score)
+            .max(0.0)
+    };
+
+    // Ungraded work past its due date is missing, not still to come
+    let as_of = resolve_as_of(grades, options.as_of.as_deref());
+    let due_dates: HashMap<String, Option<f64>> = assignment_timeline(grades, assignments).into_iter().collect();
+    let past_due = |assignment_id: &str| -> bool {
+        match (due_dates.get(assignment_id).copied().flatten(), as_of) {
+            (Some(due), Some(today)) => due <= today,
+            _ => false,
+        }
+    };
+
+    // Group grades by student (sorted for deterministic output)
+    let mut student_grades: BTreeMap<String, Vec<&Grade>> = BTreeMap::new();
+    for grade in grades {
+        student_grades.entry(grade.student_id.clone()).or_default().push(grade);
+    }
+
+    // Class-level distribution that each student is shrunk toward
+    let all_scores: Vec<f64> = grades.iter()
+        .filter(|g| g.max_score > 0.0)
+        .map(|g| (g.score / g.max_score) * 100.0)
+        .collect();
+    let class_mean = calculate_mean(&all_scores);
+    let class_std = calculate_std_deviation(&all_scores, class_mean);
+
+    // Smoothed current level from the progression engine
+    let progression = compute_learning_progression(grades, assignments, &options.progression);
+    let current_levels: HashMap<&str, f64> = progression.student_progressions.iter()
+        .filter_map(|p| {
+            p.metrics.smoothed_trajectory.last().map(|level| (p.student_id.as_str(), *level))
+        })
+        .collect();
+
+    let k = options.shrinkage_strength.max(0.0);
+    let mut models: Vec<StudentModel> = Vec::new();
+
+    for (student_id, list) in &student_grades {
+        let scores: Vec<f64> = list.iter()
+            .filter(|g| g.max_score > 0.0)
+            .map(|g| (g.score / g.max_score) * 100.0)
+            .collect();
+        let n = scores.len() as f64;
+        let mean = calculate_mean(&scores);
+        let std_dev = calculate_std_deviation(&scores, mean);
+        let level = current_levels.get(student_id.as_str()).copied().unwrap_or(mean);
+
+        let shrunk_mean = if n + k > 0.0 {
+            (n * level + k * class_mean) / (n + k)
+        } else {
+            class_mean
+        };
+        let dof = (n - 1.0).max(0.0);
+        let shrunk_std = if dof + k > 0.0 {
+            ((dof * std_dev.powi(2) + k * class_std.powi(2)) / (dof + k)).sqrt()
+        } else {
+            class_std
+        };
+
+        let mut earned_weight = 0.0;
+        let mut graded_weight = 0.0;
+        for grade in list {
+            if grade.max_score <= 0.0 {
+                continue;
+            }
+            let max_score = assignments.iter()
+                .find(|a| a.id == grade.assignment_id)
+                .map(|a| a.max_score)
+                .unwrap_or(grade.max_score);
+            let weight = weight_of(&grade.assignment_id, max_score);
+            earned_weight += weight * (grade.score / grade.max_score);
+            graded_weight += weight;
+        }
+
+        let mut remaining: Vec<f64> = Vec::new();
+        for assignment in assignments.iter().filter(|a| !list.iter().any(|g| g.assignment_id == a.id)) {
+            let weight = weight_of(&assignment.id, assignment.max_score);
+            if past_due(&assignment.id) {
+                graded_weight += weight;
+            } else {
+                remaining.push(weight);
+            }
+        }
+
+        models.push(StudentModel {
+            student_id: student_id.clone(),
+            earned_weight,
+            graded_weight,
+            remaining_weight: remaining.iter().sum(),
+            remaining,
+            mean: shrunk_mean,
+            std_dev: shrunk_std,
+        });
+    }
+
+    let remaining_assignments = assignments.iter()
+        .filter(|a| !past_due(&a.id) && !grades.iter().any(|g| g.assignment_id == a.id))
+        .count();
+
+    // Run the simulations
+    let simulations = options.simulations.max(1);
+    let mut rng = SeededRng::new(options.seed);
+    let mut letter_counts: Vec<Vec<f64>> = vec![Vec::with_capacity(simulations); LETTERS.len()];
+    let mut student_finals: Vec<Vec<f64>> = vec![Vec::with_capacity(simulations); models.len()];
+
+    for _ in 0..simulations {
+        let mut counts = [0usize; 5];
+
+        for (i, model) in models.iter().enumerate() {
+            let mut earned = model.earned_weight;
+            for weight in &model.remaining {
+                let draw = (model.mean + model.std_dev * rng.next_normal()).clamp(0.0, 100.0);
+                earned += weight * draw / 100.0;
+            }
+
+            let total_weight = model.graded_weight + model.remaining_weight;
+            let final_percentage = if total_weight > 0.0 {
+                earned / total_weight * 100.0
+            } else {
+                0.0
+            };
+
+            let letter = letter_grade(final_percentage);
+            let index = LETTERS.iter().position(|l| *l == letter).unwrap_or(4);
+            counts[index] += 1;
+            student_finals[i].push(final_percentage);
+        }
+
+        for (index, count) in counts.iter().enumerate() {
+            letter_counts[index].push(*count as f64);
+        }
+    }
+
+    let total_students = models.len();
+    let band = |letter: &str, samples: &mut Vec<f64>| -> LetterBand {
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let expected_count = calculate_mean(samples);
+        LetterBand {
+            letter: letter.to_string(),
+            expected_count,
+            lower: calculate_quantile(samples, 0.05),
+            upper: calculate_quantile(samples, 0.95),
+            expected_share: if total_students > 0 {
+                expected_count / total_students as f64
+            } else {
+                0.0
+            },
+        }
+    };
+
+    let mut d_or_f_samples: Vec<f64> = letter_counts[3].iter()
+        .zip(letter_counts[4].iter())
+        .map(|(d, f)| d + f)
+        .collect();
+    let d_or_f = band("D/F", &mut d_or_f_samples);
+
+    let letter_distribution: Vec<LetterBand> = LETTERS.iter()
+        .zip(letter_counts.iter_mut())
+        .map(|(letter, samples)| band(letter, samples))
+        .collect();
+
+    let student_outlooks: Vec<StudentOutlook> = models.iter()
+        .zip(student_finals.iter_mut())
+        .map(|(model, finals)| {
+            let mut letter_tally = [0usize; 5];
+            for value in finals.iter() {
+                let letter = letter_grade(*value);
+                letter_tally[LETTERS.iter().position(|l| *l == letter).unwrap_or(4)] += 1;
+            }
+            let most_likely = letter_tally.iter()
+                .enumerate()
+                .max_by_key(|(i, count)| (**count, std::cmp::Reverse(*i)))
+                .map(|(i, _)| LETTERS[i])
+                .unwrap_or("F");
+
+            finals.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            StudentOutlook {
+                student_id: model.student_id.clone(),
+                current_percentage: if model.graded_weight > 0.0 {
+                    model.earned_weight / model.graded_weight * 100.0
+                } else {
+                    0.0
+                },
+                expected_final: calculate_mean(finals),
+                final_lower: calculate_quantile(finals, 0.05),
+                final_upper: calculate_quantile(finals, 0.95),
+                most_likely_letter: most_likely.to_string(),
+                probability_d_or_f: (letter_tally[3] + letter_tally[4]) as f64 / simulations as f64,
+            }
+        })
+        .collect();
+
+    GradeSimulationResult {
+        simulations,
+        seed: options.seed,
+        remaining_assignments,
+        letter_distribution,
+        d_or_f,
+        student_outlooks,
+        total_students,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(student_id: &str, assignment_id: &str, score: f64) -> Grade {
+        Grade {
+            student_id: student_id.to_string(),
+            assignment_id: assignment_id.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: None,
+            due_date: None,
+        }
+    }
+
+    fn assignment(id: &str) -> Assignment {
+        Assignment {
+            id: id.to_string(),
+            name: format!("Quiz {}", id),
+            max_score: 100.0,
+            due_date: None,
+        }
+    }
+
+    #[test]
+    fn test_simulation_is_deterministic_and_bounded() {
+        let grades = vec![
+            grade("S1", "A1", 95.0), grade("S1", "A2", 92.0),
+            grade("S2", "A1", 45.0), grade("S2", "A2", 50.0),
+            grade("S3", "A1", 75.0), grade("S3", "A2", 78.0),
+        ];
+        let assignments = vec![assignment("A1"), assignment("A2"), assignment("A3"), assignment("A4")];
+        let options = SimulationOptions { simulations: 500, ..Default::default() };
+
+        let first = run_grade_simulation(&grades, &assignments, &options);
+        let second = run_grade_simulation(&grades, &assignments, &options);
+
+        assert_eq!(first.remaining_assignments, 2);
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap()
+        );
+
+        let total: f64 = first.letter_distribution.iter().map(|b| b.expected_count).sum();
+        assert!((total - 3.0).abs() < 1e-9);
+
+        let struggling = first.student_outlooks.iter().find(|s| s.student_id == "S2").unwrap();
+        let strong = first.student_outlooks.iter().find(|s| s.student_id == "S1").unwrap();
+        assert!(struggling.probability_d_or_f > strong.probability_d_or_f);
+        assert!(struggling.final_lower <= struggling.expected_final);
+        assert!(struggling.expected_final <= struggling.final_upper);
+    }
+
+    #[test]
+    fn test_past_due_missing_work_scores_zero() {
+        let dated = |id: &str, due: &str| Assignment { due_date: Some(due.to_string()), ..assignment(id) };
+        let assignments = vec![
+            dated("A1", "2024-09-01"),
+            dated("A2", "2024-09-08"),
+            dated("A3", "2024-12-01"),
+        ];
+        let submitted = |student: &str, assignment_id: &str, score: f64| Grade {
+            submitted_at: Some("2024-09-07".to_string()),
+            ..grade(student, assignment_id, score)
+        };
+        let grades = vec![
+            submitted("S1", "A1", 90.0), submitted("S1", "A2", 90.0),
+            submitted("S2", "A1", 90.0),
+        ];
+        let options = SimulationOptions {
+            simulations: 200,
+            as_of: Some("2024-09-10".to_string()),
+            ..Default::default()
+        };
+
+        let result = run_grade_simulation(&grades, &assignments, &options);
+        let missing = result.student_outlooks.iter().find(|s| s.student_id == "S2").unwrap();
+
+        assert_eq!(result.remaining_assignments, 1);
+        assert!((missing.current_percentage - 45.0).abs() < 1e-9);
+        assert!(missing.final_upper <= 100.0 * 2.0 / 3.0);
+    }
+}
@@ -0,0 +1,158 @@
+// ============================================================================
+// Trajectory Smoothing (EWMA and LOESS)
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmoothingMethod {
+    None,
+    Ewma,
+    Loess,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SmoothingOptions {
+    pub method: SmoothingMethod,
+    pub half_life: f64,  // EWMA half-life, in assignments
+    pub span: f64,       // LOESS neighbourhood as a fraction of the series (0-1]
+}
+
+impl Default for SmoothingOptions {
+    fn default() -> Self {
+        SmoothingOptions {
+            method: SmoothingMethod::Ewma,
+            half_life: 2.0,
+            span: 0.75,
+        }
+    }
+}
+
+impl SmoothingMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SmoothingMethod::None => "none",
+            SmoothingMethod::Ewma => "ewma",
+            SmoothingMethod::Loess => "loess",
+        }
+    }
+}
+
+// Smooth a score series using the configured method
+pub fn smooth_series(scores: &[f64], options: &SmoothingOptions) -> Vec<f64> {
+    match options.method {
+        SmoothingMethod::None => scores.to_vec(),
+        SmoothingMethod::Ewma => ewma(scores, options.half_life),
+        SmoothingMethod::Loess => loess(scores, options.span),
+    }
+}
+
+// Exponentially weighted moving average with a half-life in assignments
+pub fn ewma(scores: &[f64], half_life: f64) -> Vec<f64> {
+    if scores.is_empty() {
+        return vec![];
+    }
+    if half_life <= 0.0 {
+        return scores.to_vec();
+    }
+
+    // Weight of an observation halves every `half_life` steps
+    let alpha = 1.0 - 0.5_f64.powf(1.0 / half_life);
+
+    let mut smoothed = Vec::with_capacity(scores.len());
+    let mut current = scores[0];
+    smoothed.push(current);
+
+    for score in &scores[1..] {
+        current = alpha * score + (1.0 - alpha) * current;
+        smoothed.push(current);
+    }
+
+    smoothed
+}
+
+// Locally weighted linear regression (tricube kernel) over evenly spaced points
+pub fn loess(scores: &[f64], span: f64) -> Vec<f64> {
+    let n = scores.len();
+    if n < 3 {
+        return scores.to_vec();
+    }
+
+    // Neighbourhood size: at least 3 points so each local fit has a slope
+    let span = span.clamp(0.0, 1.0);
+    let window = ((span * n as f64).ceil() as usize).clamp(3, n);
+
+    (0..n)
+        .map(|i| {
+            let x0 = i as f64;
+
+            // Distance to the furthest point inside the window
+            let mut distances: Vec<f64> = (0..n).map(|j| (j as f64 - x0).abs()).collect();
+            distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let max_distance = distances[window - 1].max(1.0) * 1.0001;
+
+            let mut sum_w = 0.0;
+            let mut sum_wx = 0.0;
+            let mut sum_wy = 0.0;
+            let mut sum_wxx = 0.0;
+            let mut sum_wxy = 0.0;
+
+            for (j, score) in scores.iter().enumerate() {
+                let x = j as f64;
+                let u = (x - x0).abs() / max_distance;
+                if u >= 1.0 {
+                    continue;
+                }
+                let w = (1.0 - u.powi(3)).powi(3);
+                sum_w += w;
+                sum_wx += w * x;
+                sum_wy += w * score;
+                sum_wxx += w * x * x;
+                sum_wxy += w * x * score;
+            }
+
+            if sum_w == 0.0 {
+                return scores[i];
+            }
+
+            let x_mean = sum_wx / sum_w;
+            let y_mean = sum_wy / sum_w;
+            let denominator = sum_wxx - sum_w * x_mean * x_mean;
+
+            if denominator.abs() < 1e-12 {
+                return y_mean;
+            }
+
+            let slope = (sum_wxy - sum_w * x_mean * y_mean) / denominator;
+            y_mean + slope * (x0 - x_mean)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ewma_damps_single_outlier() {
+        let scores = [80.0, 82.0, 40.0, 81.0, 83.0];
+        let smoothed = ewma(&scores, 2.0);
+
+        assert_eq!(smoothed.len(), scores.len());
+        assert_eq!(smoothed[0], 80.0);
+        // The bad quiz pulls the curve down far less than the raw drop
+        assert!(smoothed[2] > 60.0);
+    }
+
+    #[test]
+    fn test_loess_preserves_linear_trend() {
+        let scores = [60.0, 65.0, 70.0, 75.0, 80.0, 85.0];
+        let smoothed = loess(&scores, 0.5);
+
+        for (raw, fitted) in scores.iter().zip(smoothed.iter()) {
+            assert!((raw - fitted).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,378 @@
+// ============================================================================
+// Submission Behavior Monitoring
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::{assignment_timeline, parse_timestamp};
+use crate::{calculate_quantile, index_grades, Assignment, Grade};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SubmissionOptions {
+    pub last_minute_hours: f64,   // On-time submissions this close to the deadline
+    pub drop_threshold: f64,      // Week-over-week fall in submission rate that flags a drop (0-1)
+    pub as_of: Option<String>,    // Defaults to the latest submission in the data
+}
+
+impl Default for SubmissionOptions {
+    fn default() -> Self {
+        SubmissionOptions {
+            last_minute_hours: 2.0,
+            drop_threshold: 0.25,
+            as_of: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SubmissionProfile {
+    pub expected: usize,
+    pub submitted: usize,
+    pub on_time_rate: f64,                          // 0-1 of expected
+    pub late_rate: f64,
+    pub missing_rate: f64,
+    pub median_hours_before_deadline: Option<f64>,  // Negative = late
+    pub last_minute_share: f64,                     // 0-1 of on-time submissions with timestamps
+    pub resubmissions: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WeeklySubmission {
+    pub week: i64,                    // Weeks since the first due date
+    pub expected: usize,
+    pub on_time: usize,
+    pub late: usize,
+    pub missing: usize,
+    pub submission_rate: f64,         // 0-1
+    pub change_from_previous: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentSubmissionBehavior {
+    pub student_id: String,
+    pub profile: SubmissionProfile,
+    pub weekly: Vec<WeeklySubmission>,
+    pub engagement_drop: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubmissionBehaviorResult {
+    pub students: Vec<StudentSubmissionBehavior>,
+    pub class_profile: SubmissionProfile,
+    pub class_weekly: Vec<WeeklySubmission>,
+    pub students_with_drop: Vec<String>,
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_submission_behavior(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: SubmissionOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = compute_submission_behavior(&grades, &assignments, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Outcome of one expected student x assignment submission
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum SubmissionStatus {
+    OnTime,
+    Late,
+    Missing,
+    Submitted,   // Graded but without timestamps to judge timing
+}
+
+pub(crate) struct SubmissionRecord {
+    pub assignment_id: String,
+    pub status: SubmissionStatus,
+    pub hours_before_deadline: Option<f64>,
+    pub due_day: Option<f64>,
+    pub attempts: usize,
+}
+
+// "Today" for deciding what is due: the given timestamp, else the latest
+// submission seen anywhere in the course
+pub(crate) fn resolve_as_of(grades: &[Grade], as_of: Option<&str>) -> Option<f64> {
+    as_of.and_then(parse_timestamp).or_else(|| {
+        grades.iter()
+            .filter_map(|g| g.submitted_at.as_deref().and_then(parse_timestamp))
+            .reduce(f64::max)
+    })
+}
+
+// Classify every expected submission for each student. Work due after
+// as_of is only expected once something has been handed in for it.
+pub(crate) fn submission_records(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    as_of: Option<f64>,
+) -> BTreeMap<String, Vec<SubmissionRecord>> {
+    let timeline = assignment_timeline(grades, assignments);
+    let students: BTreeSet<&str> = grades.iter().map(|g| g.student_id.as_str()).collect();
+    let index = index_grades(grades);
+
+    let mut records: BTreeMap<String, Vec<SubmissionRecord>> = BTreeMap::new();
+    for student_id in students {
+        let student_records = timeline.iter()
+            .filter_map(|(assignment_id, assignment_due)| {
+                let attempts: &[&Grade] = index.get(&(student_id, assignment_id.as_str())).map_or(&[], |list| list);
+
+                // Judge timing on the latest attempt
+                let latest = attempts.iter()
+                    .max_by(|a, b| {
+                        let a_time = a.submitted_at.as_deref().and_then(parse_timestamp);
+                        let b_time = b.submitted_at.as_deref().and_then(parse_timestamp);
+                        a_time.partial_cmp(&b_time).unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                let due_day = latest
+                    .and_then(|g| g.due_date.as_deref().and_then(parse_timestamp))
+                    .or(*assignment_due);
+                if latest.is_none() && due_day.zip(as_of).is_some_and(|(due, today)| due > today) {
+                    return None;
+                }
+
+                let (status, hours_before_deadline) = match latest {
+                    None => (SubmissionStatus::Missing, None),
+                    Some(grade) => match grade.submitted_at.as_deref().and_then(parse_timestamp) {
+                        None if grade.score == 0.0 => (SubmissionStatus::Missing, None),
+                        None => (SubmissionStatus::Submitted, None),
+                        Some(submitted) => match due_day {
+                            Some(due) => {
+                                let hours = (due - submitted) * 24.0;
+                                let status = if hours < 0.0 {
+                                    SubmissionStatus::Late
+                                } else {
+                                    SubmissionStatus::OnTime
+                                };
+                                (status, Some(hours))
+                            }
+                            None => (SubmissionStatus::Submitted, None),
+                        },
+                    },
+                };
+
+                Some(SubmissionRecord {
+                    assignment_id: assignment_id.clone(),
+                    status,
+                    hours_before_deadline,
+                    due_day,
+                    attempts: attempts.len(),
+                })
+            })
+            .collect();
+        records.insert(student_id.to_string(), student_records);
+    }
+
+    records
+}
+
+pub(crate) fn compute_submission_behavior(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &SubmissionOptions,
+) -> SubmissionBehaviorResult {
+    let as_of = resolve_as_of(grades, options.as_of.as_deref());
+    let records = submission_records(grades, assignments, as_of);
+    let first_due = records.values()
+        .flat_map(|list| list.iter().filter_map(|r| r.due_day))
+        .fold(f64::INFINITY, f64::min);
+
+    let students: Vec<StudentSubmissionBehavior> = records.iter()
+        .map(|(student_id, list)| {
+            let refs: Vec<&SubmissionRecord> = list.iter().collect();
+            let weekly = weekly_breakdown(&refs, first_due);
+            let engagement_drop = weekly.last()
+                .and_then(|w| w.change_from_previous)
+                .map(|change| change <= -options.drop_threshold)
+                .unwrap_or(false);
+
+            StudentSubmissionBehavior {
+                student_id: student_id.clone(),
+                profile: build_profile(&refs, options),
+                weekly,
+                engagement_drop,
+            }
+        })
+        .collect();
+
+    let all_records: Vec<&SubmissionRecord> = records.values().flatten().collect();
+
+    SubmissionBehaviorResult {
+        class_profile: build_profile(&all_records, options),
+        class_weekly: weekly_breakdown(&all_records, first_due),
+        students_with_drop: students.iter()
+            .filter(|s| s.engagement_drop)
+            .map(|s| s.student_id.clone())
+            .collect(),
+        total_students: students.len(),
+        students,
+    }
+}
+
+fn build_profile(records: &[&SubmissionRecord], options: &SubmissionOptions) -> SubmissionProfile {
+    let expected = records.len();
+    if expected == 0 {
+        return SubmissionProfile::default();
+    }
+
+    let count = |status: SubmissionStatus| records.iter().filter(|r| r.status == status).count();
+    let on_time = count(SubmissionStatus::OnTime);
+    let late = count(SubmissionStatus::Late);
+    let missing = count(SubmissionStatus::Missing);
+
+    let mut hours: Vec<f64> = records.iter().filter_map(|r| r.hours_before_deadline).collect();
+    hours.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let last_minute = records.iter()
+        .filter(|r| r.status == SubmissionStatus::OnTime)
+        .filter(|r| r.hours_before_deadline.is_some_and(|h| h <= options.last_minute_hours))
+        .count();
+
+    SubmissionProfile {
+        expected,
+        submitted: expected - missing,
+        on_time_rate: on_time as f64 / expected as f64,
+        late_rate: late as f64 / expected as f64,
+        missing_rate: missing as f64 / expected as f64,
+        median_hours_before_deadline: if hours.is_empty() {
+            None
+        } else {
+            Some(calculate_quantile(&hours, 0.5))
+        },
+        last_minute_share: if on_time > 0 {
+            last_minute as f64 / on_time as f64
+        } else {
+            0.0
+        },
+        resubmissions: records.iter().map(|r| r.attempts.saturating_sub(1)).sum(),
+    }
+}
+
+// Group dated records into calendar weeks from the first due date
+fn weekly_breakdown(records: &[&SubmissionRecord], first_due: f64) -> Vec<WeeklySubmission> {
+    let mut weeks: BTreeMap<i64, Vec<&SubmissionRecord>> = BTreeMap::new();
+    for record in records {
+        if let Some(due) = record.due_day {
+            let week = ((due - first_due) / 7.0).floor() as i64;
+            weeks.entry(week).or_default().push(record);
+        }
+    }
+
+    let mut previous_rate: Option<f64> = None;
+    weeks.into_iter()
+        .map(|(week, list)| {
+            let count = |status: SubmissionStatus| list.iter().filter(|r| r.status == status).count();
+            let missing = count(SubmissionStatus::Missing);
+            let submission_rate = (list.len() - missing) as f64 / list.len() as f64;
+            let change_from_previous = previous_rate.map(|previous| submission_rate - previous);
+            previous_rate = Some(submission_rate);
+
+            WeeklySubmission {
+                week,
+                expected: list.len(),
+                on_time: count(SubmissionStatus::OnTime),
+                late: count(SubmissionStatus::Late),
+                missing,
+                submission_rate,
+                change_from_previous,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(student_id: &str, assignment_id: &str, score: f64, submitted_at: Option<&str>) -> Grade {
+        Grade {
+            student_id: student_id.to_string(),
+            assignment_id: assignment_id.to_string(),
+            score,
+            max_score: 100.0,
+            submitted_at: submitted_at.map(|s| s.to_string()),
+            due_date: None,
+        }
+    }
+
+    fn assignment(id: &str, due: &str) -> Assignment {
+        Assignment {
+            id: id.to_string(),
+            name: id.to_string(),
+            max_score: 100.0,
+            due_date: Some(due.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_submission_profile_and_drop() {
+        let assignments = vec![
+            assignment("A1", "2024-09-06T23:59:00Z"),
+            assignment("A2", "2024-09-13T23:59:00Z"),
+            assignment("A3", "2024-09-20T23:59:00Z"),
+        ];
+        let grades = vec![
+            grade("S1", "A1", 80.0, Some("2024-09-06T23:00:00Z")),
+            grade("S1", "A2", 70.0, Some("2024-09-14T10:00:00Z")),
+            grade("S1", "A2", 75.0, Some("2024-09-14T12:00:00Z")),
+            grade("S2", "A1", 90.0, Some("2024-09-04T12:00:00Z")),
+            grade("S2", "A2", 88.0, Some("2024-09-11T12:00:00Z")),
+            grade("S2", "A3", 91.0, Some("2024-09-18T12:00:00Z")),
+        ];
+
+        // A3 was due after the last submission in the data
+        let options = SubmissionOptions { as_of: Some("2024-09-21".to_string()), ..Default::default() };
+        let result = compute_submission_behavior(&grades, &assignments, &options);
+        let s1 = result.students.iter().find(|s| s.student_id == "S1").unwrap();
+
+        assert_eq!(s1.profile.expected, 3);
+        assert_eq!(s1.profile.resubmissions, 1);
+        assert!((s1.profile.missing_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert!((s1.profile.late_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(s1.profile.last_minute_share, 1.0);
+        assert_eq!(s1.weekly.len(), 3);
+        assert!(s1.engagement_drop);
+        assert_eq!(result.students_with_drop, vec!["S1".to_string()]);
+        assert_eq!(result.class_profile.expected, 6);
+    }
+
+    #[test]
+    fn test_work_not_yet_due_is_not_missing() {
+        let assignments = vec![
+            assignment("A1", "2024-09-06T23:59:00Z"),
+            assignment("A2", "2024-09-13T23:59:00Z"),
+            assignment("A3", "2024-12-20T23:59:00Z"),
+        ];
+        let grades = vec![
+            grade("S1", "A1", 80.0, Some("2024-09-05T12:00:00Z")),
+            grade("S1", "A2", 85.0, Some("2024-09-12T12:00:00Z")),
+        ];
+
+        let result = compute_submission_behavior(&grades, &assignments, &SubmissionOptions::default());
+        let s1 = &result.students[0];
+        assert_eq!(s1.profile.expected, 2);
+        assert_eq!(s1.profile.missing_rate, 0.0);
+        assert!(!s1.engagement_drop);
+
+        let options: SubmissionOptions = serde_json::from_str(r#"{"as_of": "2024-12-21"}"#).unwrap();
+        let result = compute_submission_behavior(&grades, &assignments, &options);
+        assert!((result.students[0].profile.missing_rate - 1.0 / 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,336 @@
+// ============================================================================
+// Disengagement Survival Analysis (time to first run of missed assignments)
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::assignment_timeline;
+use crate::risk_model::fit_l2_logistic;
+use crate::submission::{resolve_as_of, submission_records, SubmissionStatus};
+use crate::{calculate_mean, index_grades, Assignment, Grade};
+
+// Covariates of the discrete-time hazard model, measured before each assignment
+pub(crate) const HAZARD_FEATURES: &[&str] = &["period", "average", "missing_rate", "current_missing_run", "recent_decline"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SurvivalOptions {
+    pub missing_run: usize,               // Consecutive missed assignments that count as disengaged
+    pub horizon_days: f64,                // Prediction window
+    pub as_of: Option<String>,            // Defaults to the latest submission in the data
+    pub assignments_per_horizon: usize,   // Fallback when upcoming due dates are unknown
+    pub l2_penalty: f64,
+}
+
+impl Default for SurvivalOptions {
+    fn default() -> Self {
+        SurvivalOptions {
+            missing_run: 2,
+            horizon_days: 14.0,
+            as_of: None,
+            assignments_per_horizon: 2,
+            l2_penalty: 1.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SurvivalPoint {
+    pub period: usize,                    // Assignment index in course order
+    pub assignment_id: String,
+    pub at_risk: usize,
+    pub events: usize,
+    pub survival: f64,                    // Kaplan-Meier estimate after this assignment
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HazardCoefficient {
+    pub feature: String,
+    pub weight: f64,                      // Log-odds per standard deviation
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentHazard {
+    pub student_id: String,
+    pub disengaged: bool,                 // Already had the run of misses
+    pub event_period: Option<usize>,
+    pub current_missing_run: usize,
+    pub next_assignment_hazard: Option<f64>,
+    pub horizon_probability: f64,         // Chance of disengaging within the horizon
+    pub upcoming_assignments: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SurvivalResult {
+    pub curve: Vec<SurvivalPoint>,
+    pub median_periods_to_event: Option<usize>,
+    pub coefficients: Vec<HazardCoefficient>,
+    pub person_periods: usize,
+    pub events: usize,
+    pub students: Vec<StudentHazard>,
+    pub horizon_days: f64,
+}
+
+#[wasm_bindgen]
+pub fn analyze_disengagement_survival(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: SurvivalOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let result = compute_survival(&grades, &assignments, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// One student's observed history: (missed, percentage) per past assignment
+struct History {
+    student_id: String,
+    items: Vec<(bool, f64)>,
+}
+
+impl History {
+    // Period at which the run of misses first reaches `run`
+    fn event_period(&self, run: usize) -> Option<usize> {
+        let mut current = 0;
+        for (period, (missed, _)) in self.items.iter().enumerate() {
+            current = if *missed { current + 1 } else { 0 };
+            if current >= run.max(1) {
+                return Some(period);
+            }
+        }
+        None
+    }
+
+    // Covariates known before `period`, matching HAZARD_FEATURES
+    fn covariates(&self, period: usize) -> Vec<f64> {
+        let past = &self.items[..period];
+        let scores: Vec<f64> = past.iter().map(|(_, p)| *p).collect();
+        let average = calculate_mean(&scores);
+        let missed = past.iter().filter(|(m, _)| *m).count();
+        let current_run = past.iter().rev().take_while(|(m, _)| *m).count();
+        let recent = &scores[scores.len().saturating_sub(3)..];
+
+        vec![
+            period as f64,
+            average,
+            if past.is_empty() { 0.0 } else { missed as f64 / past.len() as f64 * 100.0 },
+            current_run as f64,
+            if scores.len() >= 3 { (average - calculate_mean(recent)).max(0.0) } else { 0.0 },
+        ]
+    }
+}
+
+pub(crate) fn compute_survival(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &SurvivalOptions,
+) -> SurvivalResult {
+    let timeline = assignment_timeline(grades, assignments);
+    let as_of = resolve_as_of(grades, options.as_of.as_deref());
+
+    // Assignments not yet due are neither missed nor observed
+    let is_past = |due: Option<f64>| match (due, as_of) {
+        (Some(due), Some(today)) => due <= today,
+        _ => true,
+    };
+    let past_ids: Vec<&str> = timeline.iter()
+        .filter(|(_, due)| is_past(*due))
+        .map(|(id, _)| id.as_str())
+        .collect();
+    let upcoming = match as_of {
+        Some(today) if timeline.iter().any(|(_, due)| due.is_some()) => timeline.iter()
+            .filter(|(_, due)| due.is_some_and(|d| d > today && d <= today + options.horizon_days))
+            .count(),
+        _ => options.assignments_per_horizon,
+    };
+
+    let index = index_grades(grades);
+    let histories: Vec<History> = submission_records(grades, assignments, as_of).into_iter()
+        .map(|(student_id, records)| {
+            let items = records.iter()
+                .filter(|r| past_ids.contains(&r.assignment_id.as_str()))
+                .map(|r| {
+                    let percentage = index.get(&(student_id.as_str(), r.assignment_id.as_str()))
+                        .into_iter()
+                        .flatten()
+                        .filter(|g| g.max_score > 0.0)
+                        .map(|g| (g.score / g.max_score) * 100.0)
+                        .reduce(f64::max)
+                        .unwrap_or(0.0);
+                    (r.status == SubmissionStatus::Missing, percentage)
+                })
+                .collect();
+            History { student_id, items }
+        })
+        .collect();
+
+    // Kaplan-Meier: everyone is observed over the same past assignments, so
+    // students without an event are censored at the end
+    let event_periods: Vec<Option<usize>> = histories.iter().map(|h| h.event_period(options.missing_run)).collect();
+    let mut survival = 1.0;
+    let mut median_periods_to_event = None;
+    let curve: Vec<SurvivalPoint> = past_ids.iter()
+        .enumerate()
+        .map(|(period, assignment_id)| {
+            let at_risk = event_periods.iter().filter(|e| e.is_none_or(|p| p >= period)).count();
+            let events = event_periods.iter().filter(|e| **e == Some(period)).count();
+            if at_risk > 0 {
+                survival *= 1.0 - events as f64 / at_risk as f64;
+            }
+            if survival <= 0.5 && median_periods_to_event.is_none() {
+                median_periods_to_event = Some(period);
+            }
+            SurvivalPoint {
+                period,
+                assignment_id: assignment_id.to_string(),
+                at_risk,
+                events,
+                survival,
+            }
+        })
+        .collect();
+
+    // Person-period rows for the discrete-time hazard model
+    let mut rows: Vec<(Vec<f64>, bool)> = Vec::new();
+    for (history, event) in histories.iter().zip(&event_periods) {
+        let last = event.unwrap_or(history.items.len().saturating_sub(1));
+        for period in 1..=last.min(history.items.len().saturating_sub(1)) {
+            rows.push((history.covariates(period), *event == Some(period)));
+        }
+    }
+    let events = rows.iter().filter(|r| r.1).count();
+    let fit = (events > 0 && events < rows.len()).then(|| fit_l2_logistic(&rows, options.l2_penalty, 1000, 0.5));
+
+    let students: Vec<StudentHazard> = histories.iter()
+        .zip(&event_periods)
+        .map(|(history, event)| {
+            let current_missing_run = history.items.iter().rev().take_while(|(m, _)| *m).count();
+            if event.is_some() {
+                return StudentHazard {
+                    student_id: history.student_id.clone(),
+                    disengaged: true,
+                    event_period: *event,
+                    current_missing_run,
+                    next_assignment_hazard: None,
+                    horizon_probability: 1.0,
+                    upcoming_assignments: upcoming,
+                };
+            }
+
+            // Each later hazard is conditional on surviving the one before, so
+            // the projection assumes the student hands in work at their usual level
+            let next_assignment_hazard = fit.as_ref().map(|f| f.predict(&history.covariates(history.items.len())));
+            let horizon_probability = match &fit {
+                Some(fit) => {
+                    let submitted: Vec<f64> = history.items.iter().filter(|(m, _)| !*m).map(|(_, p)| *p).collect();
+                    let usual = calculate_mean(&submitted);
+                    let mut projected = History { student_id: String::new(), items: history.items.clone() };
+                    let mut survive = 1.0;
+                    for _ in 0..upcoming {
+                        survive *= 1.0 - fit.predict(&projected.covariates(projected.items.len()));
+                        projected.items.push((false, usual));
+                    }
+                    1.0 - survive
+                }
+                None => 0.0,
+            };
+
+            StudentHazard {
+                student_id: history.student_id.clone(),
+                disengaged: false,
+                event_period: None,
+                current_missing_run,
+                next_assignment_hazard,
+                horizon_probability,
+                upcoming_assignments: upcoming,
+            }
+        })
+        .collect();
+
+    SurvivalResult {
+        curve,
+        median_periods_to_event,
+        coefficients: fit.as_ref()
+            .map(|f| {
+                HAZARD_FEATURES.iter()
+                    .zip(&f.weights)
+                    .map(|(feature, weight)| HazardCoefficient { feature: feature.to_string(), weight: *weight })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        person_periods: rows.len(),
+        events,
+        students,
+        horizon_days: options.horizon_days,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kaplan_meier_and_hazard() {
+        let assignments: Vec<Assignment> = (0..6)
+            .map(|i| Assignment {
+                id: format!("A{}", i),
+                name: format!("Homework {}", i),
+                max_score: 100.0,
+                due_date: None,
+            })
+            .collect();
+        // Missed assignment indices per student
+        let missed: [(&str, &[usize]); 6] = [
+            ("S1", &[2, 3]),
+            ("S2", &[4, 5]),
+            ("S3", &[1]),
+            ("S4", &[]),
+            ("S5", &[3]),
+            ("S6", &[5]),
+        ];
+        let mut grades = Vec::new();
+        for (student, gaps) in missed {
+            for a in 0..6 {
+                if !gaps.contains(&a) {
+                    grades.push(Grade {
+                        student_id: student.to_string(),
+                        assignment_id: format!("A{}", a),
+                        score: 80.0,
+                        max_score: 100.0,
+                        submitted_at: None,
+                        due_date: None,
+                    });
+                }
+            }
+        }
+
+        let result = compute_survival(&grades, &assignments, &SurvivalOptions::default());
+
+        assert_eq!(result.curve.len(), 6);
+        assert_eq!(result.curve[3].events, 1);
+        assert!((result.curve[3].survival - 5.0 / 6.0).abs() < 1e-9);
+        assert!((result.curve[5].survival - 5.0 / 6.0 * 4.0 / 5.0).abs() < 1e-9);
+        assert_eq!(result.events, 2);
+
+        let s1 = result.students.iter().find(|s| s.student_id == "S1").unwrap();
+        assert!(s1.disengaged);
+        assert_eq!(s1.event_period, Some(3));
+        let s6 = result.students.iter().find(|s| s.student_id == "S6").unwrap();
+        let s4 = result.students.iter().find(|s| s.student_id == "S4").unwrap();
+        assert_eq!(s6.current_missing_run, 1);
+        assert!(s6.horizon_probability > s4.horizon_probability);
+        assert_eq!(s4.upcoming_assignments, 2);
+    }
+}
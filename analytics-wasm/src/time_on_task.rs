@@ -0,0 +1,501 @@
+// ============================================================================
+// Time-on-Task Analysis (LMS activity events vs chapter performance)
+// ============================================================================
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::dates::parse_timestamp;
+use crate::knowledge_gaps::{compute_knowledge_gaps, KnowledgeGapOptions};
+use crate::topics::{TopicMapper, TopicMapping};
+use crate::{calculate_correlation, calculate_mean, calculate_quantile, extract_chapter_name, Assignment, Grade};
+
+// One stretch of LMS activity on a content item
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActivityEvent {
+    pub student_id: String,
+    pub content_id: String,
+    #[serde(default)]
+    pub content_name: Option<String>,
+    #[serde(default)]
+    pub chapter: Option<String>,           // Canonicalized; falls back to the content name's chapter
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub ended_at: Option<String>,
+    #[serde(default)]
+    pub duration_minutes: Option<f64>,     // Wins over started_at/ended_at
+}
+
+impl ActivityEvent {
+    pub(crate) fn minutes(&self) -> Option<f64> {
+        if let Some(minutes) = self.duration_minutes {
+            return Some(minutes.max(0.0));
+        }
+        let start = parse_timestamp(self.started_at.as_deref()?)?;
+        let end = parse_timestamp(self.ended_at.as_deref()?)?;
+        Some(((end - start) * 1440.0).max(0.0))
+    }
+
+    pub(crate) fn chapter_name(&self, topics: &TopicMapper) -> String {
+        match &self.chapter {
+            Some(chapter) => topics.canonical(chapter),
+            None => extract_chapter_name(self.content_name.as_deref().unwrap_or(&self.content_id), topics),
+        }
+    }
+}
+
+// A report row left out of an import, with the reason
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SkippedRow {
+    pub row: usize,                        // 1-based line in the report, header included
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ActivityImport {
+    pub events: Vec<ActivityEvent>,
+    pub skipped: Vec<SkippedRow>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TimeOnTaskOptions {
+    pub max_session_minutes: f64,     // Caps sessions left open in the LMS
+    pub low_score_threshold: f64,     // Average (0-100) below which results count as low
+    pub high_effort_quantile: f64,    // Class quantile (0-1) of total minutes that counts as high effort
+    pub topics: TopicMapping,         // Applied to assignments and to content names
+}
+
+impl Default for TimeOnTaskOptions {
+    fn default() -> Self {
+        TimeOnTaskOptions {
+            max_session_minutes: 240.0,
+            low_score_threshold: 70.0,
+            high_effort_quantile: 0.5,
+            topics: TopicMapping::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChapterTimeOnTask {
+    pub chapter: String,
+    pub students: usize,               // Students with both time and a score
+    pub mean_minutes: f64,
+    pub mean_score: f64,
+    pub correlation: Option<f64>,      // Minutes vs chapter score; None below 3 students
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StudentTimeOnTask {
+    pub student_id: String,
+    pub total_minutes: f64,
+    pub chapter_minutes: BTreeMap<String, f64>,
+    pub average_score: Option<f64>,
+    pub effort_level: String,          // "high", "low"
+    pub quadrant: String,              // e.g. "high_effort_low_result"
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TimeOnTaskResult {
+    pub chapters: Vec<ChapterTimeOnTask>,
+    pub students: Vec<StudentTimeOnTask>,
+    pub overall_correlation: Option<f64>,        // Across all student x chapter cells
+    pub high_effort_low_result: Vec<String>,     // Working hard without payoff: study skills support
+    pub low_effort_low_result: Vec<String>,      // Not engaging: outreach
+    pub skipped_events: usize,                   // Events with no usable duration
+    pub total_students: usize,
+}
+
+#[wasm_bindgen]
+pub fn analyze_time_on_task(
+    grades_json: &str,
+    assignments_json: &str,
+    activity_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let events: Vec<ActivityEvent> = serde_json::from_str(activity_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse activity: {}", e)))?;
+
+    let options: TimeOnTaskOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let topics = options.topics.compile()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let result = compute_time_on_task(&grades, &assignments, &events, &options, &topics);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Convert a Blackboard course activity report (CSV) into activity events JSON
+#[wasm_bindgen]
+pub fn import_blackboard_activity(csv_text: &str) -> Result<String, JsValue> {
+    let import = parse_blackboard_activity_csv(csv_text)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse activity report: {}", e)))?;
+
+    serde_json::to_string(&import)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Header aliases seen across Blackboard Learn/Ultra activity exports
+const STUDENT_COLUMNS: &[&str] = &["username", "student id", "user id", "student", "user"];
+const CONTENT_ID_COLUMNS: &[&str] = &["content id", "content_pk1", "item id"];
+const CONTENT_NAME_COLUMNS: &[&str] = &["content item", "content title", "content", "item", "title"];
+const CHAPTER_COLUMNS: &[&str] = &["chapter", "module", "learning module", "folder"];
+const TIMESTAMP_COLUMNS: &[&str] = &["timestamp", "date", "access date", "last access"];
+const START_COLUMNS: &[&str] = &["start time", "start", "session start"];
+const END_COLUMNS: &[&str] = &["end time", "end", "session end"];
+const DURATION_COLUMNS: &[&str] = &["time spent", "duration", "minutes", "time in content", "hours in course"];
+
+// Fails only when the header is unusable; bad rows are reported and skipped
+pub(crate) fn parse_blackboard_activity_csv(csv_text: &str) -> Result<ActivityImport, String> {
+    let mut rows = parse_csv(csv_text).into_iter();
+    let header: Vec<String> = rows.next()
+        .ok_or("report is empty")?
+        .iter()
+        .map(|h| h.trim().trim_start_matches('\u{feff}').to_lowercase())
+        .collect();
+
+    let column = |aliases: &[&str]| aliases.iter().find_map(|alias| header.iter().position(|h| h == alias));
+    let student_col = column(STUDENT_COLUMNS)
+        .ok_or_else(|| format!("no student column (expected one of: {})", STUDENT_COLUMNS.join(", ")))?;
+    let content_id_col = column(CONTENT_ID_COLUMNS);
+    let content_name_col = column(CONTENT_NAME_COLUMNS);
+    let chapter_col = column(CHAPTER_COLUMNS);
+    let timestamp_col = column(TIMESTAMP_COLUMNS);
+    let start_col = column(START_COLUMNS);
+    let end_col = column(END_COLUMNS);
+    let duration_col = column(DURATION_COLUMNS);
+    // "Hours in Course" is reported in hours, every other duration column in minutes
+    let duration_in_hours = duration_col.is_some_and(|c| header[c] == "hours in course");
+
+    if duration_col.is_none() && (start_col.is_none() || end_col.is_none()) {
+        return Err("no duration column and no start/end time columns".to_string());
+    }
+
+    let mut events = Vec::new();
+    let mut skipped = Vec::new();
+    for (line, row) in rows.enumerate() {
+        let field = |col: Option<usize>| {
+            col.and_then(|c| row.get(c))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+        };
+        let Some(student_id) = field(Some(student_col)) else {
+            continue;
+        };
+
+        let duration_minutes = match field(duration_col) {
+            Some(raw) => match parse_duration_minutes(&raw) {
+                Some(minutes) => Some(if duration_in_hours && !raw.contains(':') { minutes * 60.0 } else { minutes }),
+                None => {
+                    skipped.push(SkippedRow { row: line + 2, reason: format!("unreadable duration \"{}\"", raw) });
+                    continue;
+                }
+            },
+            None => None,
+        };
+        let content_name = field(content_name_col);
+
+        events.push(ActivityEvent {
+            student_id,
+            content_id: field(content_id_col)
+                .or_else(|| content_name.clone())
+                .unwrap_or_else(|| "course".to_string()),
+            content_name,
+            chapter: field(chapter_col),
+            timestamp: field(timestamp_col),
+            started_at: field(start_col),
+            ended_at: field(end_col),
+            duration_minutes,
+        });
+    }
+
+    Ok(ActivityImport { events, skipped })
+}
+
+// Minutes from "90", "1.5", "01:30:00" (h:m:s), "12:30" (m:s), "1h 20m 5s" or "1h30m"
+fn parse_duration_minutes(raw: &str) -> Option<f64> {
+    let raw = raw.trim();
+    if let Ok(minutes) = raw.parse::<f64>() {
+        return Some(minutes);
+    }
+
+    if raw.contains(':') {
+        let parts: Vec<f64> = raw.split(':').map(|p| p.trim().parse().ok()).collect::<Option<_>>()?;
+        return match parts.as_slice() {
+            [hours, minutes, seconds] => Some(hours * 60.0 + minutes + seconds / 60.0),
+            [minutes, seconds] => Some(minutes + seconds / 60.0),
+            _ => None,
+        };
+    }
+
+    // Alternating number and unit runs, with or without spaces between them
+    let compact: String = raw.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    let mut rest = compact.as_str();
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        let unit_start = rest.find(|c: char| c.is_alphabetic())?;
+        let value: f64 = rest[..unit_start].parse().ok()?;
+        let unit_end = rest[unit_start..].find(|c: char| !c.is_alphabetic()).map_or(rest.len(), |i| unit_start + i);
+        total += match &rest[unit_start..unit_end] {
+            "h" | "hr" | "hrs" | "hour" | "hours" => value * 60.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => value,
+            "s" | "sec" | "secs" | "second" | "seconds" => value / 60.0,
+            _ => return None,
+        };
+        rest = &rest[unit_end..];
+    }
+    (!compact.is_empty()).then_some(total)
+}
+
+// RFC 4180 style rows: quoted fields, doubled quotes, CRLF or LF line ends
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|f| !f.trim().is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        if row.iter().any(|f| !f.trim().is_empty()) {
+            rows.push(row);
+        }
+    }
+
+    rows
+}
+
+pub(crate) fn compute_time_on_task(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    events: &[ActivityEvent],
+    options: &TimeOnTaskOptions,
+    topics: &TopicMapper,
+) -> TimeOnTaskResult {
+    // student -> chapter -> minutes
+    let mut minutes: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
+    let mut skipped_events = 0;
+    for event in events {
+        match event.minutes() {
+            Some(value) => {
+                *minutes
+                    .entry(event.student_id.clone())
+                    .or_default()
+                    .entry(event.chapter_name(topics))
+                    .or_default() += value.min(options.max_session_minutes);
+            }
+            None => skipped_events += 1,
+        }
+    }
+
+    let gap_options = KnowledgeGapOptions { topics: options.topics.clone(), ..KnowledgeGapOptions::default() };
+    let gaps = compute_knowledge_gaps(grades, assignments, &gap_options, topics);
+    let matrix = &gaps.matrix;
+    let chapter_score = |student_id: &str, chapter: &str| -> Option<f64> {
+        let s = matrix.students.iter().position(|id| id == student_id)?;
+        let c = matrix.chapters.iter().position(|ch| ch == chapter)?;
+        matrix.cells[s][c]
+    };
+
+    // Per-chapter minutes vs score across students
+    let mut all_minutes: Vec<f64> = Vec::new();
+    let mut all_scores: Vec<f64> = Vec::new();
+    let chapters: Vec<ChapterTimeOnTask> = matrix.chapters.iter()
+        .map(|chapter| {
+            let (xs, ys): (Vec<f64>, Vec<f64>) = minutes.iter()
+                .filter_map(|(student_id, by_chapter)| {
+                    Some((*by_chapter.get(chapter)?, chapter_score(student_id, chapter)?))
+                })
+                .unzip();
+            all_minutes.extend(&xs);
+            all_scores.extend(&ys);
+
+            ChapterTimeOnTask {
+                chapter: chapter.clone(),
+                students: xs.len(),
+                mean_minutes: calculate_mean(&xs),
+                mean_score: calculate_mean(&ys),
+                correlation: (xs.len() >= 3).then(|| calculate_correlation(&xs, &ys)),
+            }
+        })
+        .collect();
+
+    // Effort cut-off from the class distribution of total minutes
+    let mut student_ids: Vec<String> = matrix.students.clone();
+    for student_id in minutes.keys() {
+        if !student_ids.contains(student_id) {
+            student_ids.push(student_id.clone());
+        }
+    }
+    student_ids.sort();
+
+    let totals: BTreeMap<&str, f64> = student_ids.iter()
+        .map(|id| (id.as_str(), minutes.get(id).map(|m| m.values().sum()).unwrap_or(0.0)))
+        .collect();
+    let mut sorted_totals: Vec<f64> = totals.values().copied().collect();
+    sorted_totals.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let effort_cutoff = calculate_quantile(&sorted_totals, options.high_effort_quantile);
+
+    let mut high_effort_low_result = Vec::new();
+    let mut low_effort_low_result = Vec::new();
+    let students: Vec<StudentTimeOnTask> = student_ids.iter()
+        .map(|student_id| {
+            let total_minutes = totals[student_id.as_str()];
+            let average_score = gaps.students.iter()
+                .find(|s| s.student_id == *student_id)
+                .map(|s| s.average);
+
+            let effort_level = if total_minutes > 0.0 && total_minutes >= effort_cutoff { "high" } else { "low" };
+            let result_level = match average_score {
+                Some(score) if score < options.low_score_threshold => "low",
+                Some(_) => "high",
+                None => "unknown",
+            };
+            if result_level == "low" {
+                match effort_level {
+                    "high" => high_effort_low_result.push(student_id.clone()),
+                    _ => low_effort_low_result.push(student_id.clone()),
+                }
+            }
+
+            StudentTimeOnTask {
+                student_id: student_id.clone(),
+                total_minutes,
+                chapter_minutes: minutes.get(student_id).cloned().unwrap_or_default(),
+                average_score,
+                effort_level: effort_level.to_string(),
+                quadrant: format!("{}_effort_{}_result", effort_level, result_level),
+            }
+        })
+        .collect();
+
+    TimeOnTaskResult {
+        chapters,
+        overall_correlation: (all_minutes.len() >= 3)
+            .then(|| calculate_correlation(&all_minutes, &all_scores)),
+        high_effort_low_result,
+        low_effort_low_result,
+        skipped_events,
+        total_students: students.len(),
+        students,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topics::default_mapper;
+
+    #[test]
+    fn test_blackboard_csv_import() {
+        let topics = default_mapper();
+        let csv = "Username,Content Item,Module,Time Spent,Start Time\r\n\
+                   jdoe,\"Reading: Limits, Part 1\",Chapter 1,00:45:00,2024-09-02T10:00:00Z\r\n\
+                   jdoe,Chapter 2 Video,,1h 30m,2024-09-09T10:00:00Z\r\n\
+                   asmith,Chapter 2 Video,,20,\r\n\
+                   asmith,Chapter 3 Video,,about an hour,\r\n\
+                   blee,Chapter 3 Video,,1h30m,\r\n";
+
+        let import = parse_blackboard_activity_csv(csv).unwrap();
+        let events = &import.events;
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(import.skipped.len(), 1);
+        assert_eq!(import.skipped[0].row, 5);
+        assert_eq!(events[3].minutes(), Some(90.0));
+        assert_eq!(events[0].content_name.as_deref(), Some("Reading: Limits, Part 1"));
+        assert_eq!(events[0].chapter_name(topics), "Chapter 1");
+        assert_eq!(events[0].minutes(), Some(45.0));
+        assert_eq!(events[1].chapter_name(topics), "Chapter 2");
+        let abbreviated = ActivityEvent { chapter: Some("Ch.3".to_string()), ..events[0].clone() };
+        assert_eq!(abbreviated.chapter_name(topics), "Chapter 3");
+        assert_eq!(events[1].minutes(), Some(90.0));
+        assert_eq!(events[2].minutes(), Some(20.0));
+        assert!(parse_blackboard_activity_csv("Name,Grade\nX,90\n").is_err());
+    }
+
+    #[test]
+    fn test_effort_quadrants_and_correlation() {
+        let assignments = vec![Assignment {
+            id: "A1".to_string(),
+            name: "Limits Quiz".to_string(),
+            max_score: 100.0,
+            due_date: None,
+        }];
+        let rows = [("S1", 50.0, 300.0), ("S2", 90.0, 200.0), ("S3", 55.0, 20.0), ("S4", 80.0, 150.0)];
+        let grades: Vec<Grade> = rows.iter()
+            .map(|(student, score, _)| Grade {
+                student_id: student.to_string(),
+                assignment_id: "A1".to_string(),
+                score: *score,
+                max_score: 100.0,
+                submitted_at: None,
+                due_date: None,
+            })
+            .collect();
+        let events: Vec<ActivityEvent> = rows.iter()
+            .map(|(student, _, minutes)| ActivityEvent {
+                student_id: student.to_string(),
+                content_id: "C1".to_string(),
+                content_name: Some("Notes on limits".to_string()),
+                chapter: None,
+                timestamp: None,
+                started_at: None,
+                ended_at: None,
+                duration_minutes: Some(*minutes),
+            })
+            .collect();
+
+        // A course-specific pattern applies to content names as well as assignments
+        let options: TimeOnTaskOptions = serde_json::from_str(
+            r#"{"topics": {"patterns": [{"pattern": "(?i)limits", "topic": "Limits"}]}}"#,
+        ).unwrap();
+        let result = compute_time_on_task(&grades, &assignments, &events, &options, &options.topics.compile().unwrap());
+
+        // S1's 300 minutes are capped at 240
+        assert_eq!(result.students[0].total_minutes, 240.0);
+        assert_eq!(result.chapters[0].chapter, "Limits");
+        assert_eq!(result.chapters[0].students, 4);
+        assert!(result.chapters[0].correlation.is_some());
+        assert_eq!(result.high_effort_low_result, vec!["S1".to_string()]);
+        assert_eq!(result.low_effort_low_result, vec!["S3".to_string()]);
+        assert_eq!(result.students[1].quadrant, "high_effort_high_result");
+    }
+}
@@ -0,0 +1,349 @@
+// ============================================================================
+// Topic Clustering (proposed topics from assignment names and score patterns)
+// ============================================================================
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::topics::{TopicMapper, TopicMapping};
+use crate::{calculate_correlation, Assignment, Grade};
+
+// Words that describe the assessment rather than its topic
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "the", "of", "on", "in", "to", "for", "with", "by", "at", "from", "into",
+    "intro", "introduction", "review", "practice", "part", "final", "midterm", "quiz", "quizzes",
+    "exam", "test", "lab", "labs", "homework", "hw", "assignment", "project", "problem", "problems",
+    "set", "pset", "exercise", "exercises", "worksheet", "reading", "check", "activity", "bonus",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TopicClusterOptions {
+    pub descriptions: BTreeMap<String, String>,   // assignment_id -> description text
+    pub text_weight: f64,           // Share of similarity from names (rest from score correlation)
+    pub similarity_threshold: f64,  // Stop merging below this average-linkage similarity
+    pub min_common_students: usize, // Students needed before a score correlation counts
+    pub only_unmapped: bool,        // Leave assignments the topic mapping already places
+    pub topics: TopicMapping,
+}
+
+impl Default for TopicClusterOptions {
+    fn default() -> Self {
+        TopicClusterOptions {
+            descriptions: BTreeMap::new(),
+            text_weight: 0.6,
+            similarity_threshold: 0.35,
+            min_common_students: 3,
+            only_unmapped: true,
+            topics: TopicMapping::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TopicCluster {
+    pub label: String,                // Proposed topic name from the top terms
+    pub assignment_ids: Vec<String>,
+    pub assignment_names: Vec<String>,
+    pub top_terms: Vec<String>,
+    pub cohesion: f64,                // Mean pairwise similarity within the cluster
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TopicClusterResult {
+    pub clusters: Vec<TopicCluster>,
+    pub unclustered: Vec<String>,     // Assignments that joined no cluster
+    pub considered: usize,
+    pub suggested_mapping: TopicMapping,  // Accepted clusters as overrides for the topic options
+}
+
+#[wasm_bindgen]
+pub fn propose_topic_clusters(
+    grades_json: &str,
+    assignments_json: &str,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    // Parse input data
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let assignments: Vec<Assignment> = serde_json::from_str(assignments_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let options: TopicClusterOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse options: {}", e)))?;
+
+    let topics = options.topics.compile()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let result = cluster_topics(&grades, &assignments, &options, &topics);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// Lowercased content words; "Big-O" stays one token, plural "s" is dropped
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '-'))
+        .map(|w| w.trim_matches('-'))
+        .filter(|w| !w.is_empty() && !w.chars().all(|c| c.is_ascii_digit() || c == '-'))
+        .filter(|w| !STOPWORDS.contains(w))
+        .map(|w| {
+            if w.len() > 3 && w.ends_with('s') && !w.ends_with("ss") {
+                w[..w.len() - 1].to_string()
+            } else {
+                w.to_string()
+            }
+        })
+        .collect()
+}
+
+// TF-IDF weights per document
+fn tf_idf(documents: &[Vec<String>]) -> Vec<BTreeMap<String, f64>> {
+    let n = documents.len() as f64;
+    let mut document_frequency: BTreeMap<&str, usize> = BTreeMap::new();
+    for document in documents {
+        let mut seen: Vec<&str> = document.iter().map(|t| t.as_str()).collect();
+        seen.sort();
+        seen.dedup();
+        for term in seen {
+            *document_frequency.entry(term).or_default() += 1;
+        }
+    }
+
+    documents.iter()
+        .map(|document| {
+            let mut weights: BTreeMap<String, f64> = BTreeMap::new();
+            for term in document {
+                *weights.entry(term.clone()).or_default() += 1.0 / document.len() as f64;
+            }
+            for (term, weight) in weights.iter_mut() {
+                // Smoothed so terms shared by every document still count
+                let df = document_frequency[term.as_str()] as f64;
+                *weight *= ((1.0 + n) / (1.0 + df)).ln() + 1.0;
+            }
+            weights
+        })
+        .collect()
+}
+
+fn cosine(a: &BTreeMap<String, f64>, b: &BTreeMap<String, f64>) -> f64 {
+    let dot: f64 = a.iter().filter_map(|(term, w)| b.get(term).map(|v| w * v)).sum();
+    let norm = |v: &BTreeMap<String, f64>| v.values().map(|w| w * w).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator > 0.0 { dot / denominator } else { 0.0 }
+}
+
+pub(crate) fn cluster_topics(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    options: &TopicClusterOptions,
+    topics: &TopicMapper,
+) -> TopicClusterResult {
+    let candidates: Vec<&Assignment> = assignments.iter()
+        .filter(|a| !options.only_unmapped || topics.topics(a).is_empty())
+        .collect();
+    let n = candidates.len();
+
+    let documents: Vec<Vec<String>> = candidates.iter()
+        .map(|a| {
+            let description = options.descriptions.get(&a.id).map(|d| d.as_str()).unwrap_or("");
+            tokenize(&format!("{} {}", a.name, description))
+        })
+        .collect();
+    let vectors = tf_idf(&documents);
+
+    // student -> percentage per candidate assignment
+    let scores: Vec<BTreeMap<&str, f64>> = candidates.iter()
+        .map(|a| {
+            grades.iter()
+                .filter(|g| g.assignment_id == a.id && g.max_score > 0.0)
+                .map(|g| (g.student_id.as_str(), (g.score / g.max_score) * 100.0))
+                .collect()
+        })
+        .collect();
+
+    let text_weight = options.text_weight.clamp(0.0, 1.0);
+    let mut similarity = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let (xs, ys): (Vec<f64>, Vec<f64>) = scores[i].iter()
+                .filter_map(|(student, x)| scores[j].get(student).map(|y| (*x, *y)))
+                .unzip();
+            // Without enough shared students the names carry the full weight
+            let combined = if xs.len() >= options.min_common_students.max(2) {
+                let correlation = calculate_correlation(&xs, &ys).max(0.0);
+                text_weight * cosine(&vectors[i], &vectors[j]) + (1.0 - text_weight) * correlation
+            } else {
+                cosine(&vectors[i], &vectors[j])
+            };
+            similarity[i][j] = combined;
+            similarity[j][i] = combined;
+        }
+    }
+
+    // Average-linkage agglomerative clustering until no pair is similar enough
+    let mut groups: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let similarity = &similarity;
+    let linkage = |a: &[usize], b: &[usize]| -> f64 {
+        let total: f64 = a.iter().flat_map(|i| b.iter().map(move |j| similarity[*i][*j])).sum();
+        total / (a.len() * b.len()) as f64
+    };
+    loop {
+        let mut best: Option<(usize, usize, f64)> = None;
+        for a in 0..groups.len() {
+            for b in (a + 1)..groups.len() {
+                let value = linkage(&groups[a], &groups[b]);
+                if value >= options.similarity_threshold && best.is_none_or(|(_, _, v)| value > v) {
+                    best = Some((a, b, value));
+                }
+            }
+        }
+        let Some((a, b, _)) = best else {
+            break;
+        };
+        let merged = groups.remove(b);
+        groups[a].extend(merged);
+    }
+
+    let mut clusters: Vec<TopicCluster> = Vec::new();
+    let mut unclustered: Vec<String> = Vec::new();
+    for group in &groups {
+        if group.len() < 2 {
+            unclustered.extend(group.iter().map(|i| candidates[*i].id.clone()));
+            continue;
+        }
+
+        // Rank terms by summed weight, preferring terms most members share
+        let mut term_scores: BTreeMap<&str, (usize, f64)> = BTreeMap::new();
+        for i in group {
+            for (term, weight) in &vectors[*i] {
+                let entry = term_scores.entry(term.as_str()).or_default();
+                entry.0 += 1;
+                entry.1 += weight;
+            }
+        }
+        let mut ranked: Vec<(&str, (usize, f64))> = term_scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.0.cmp(&a.1.0).then(b.1.1.partial_cmp(&a.1.1).unwrap()).then(a.0.cmp(b.0)));
+        let top_terms: Vec<String> = ranked.iter().take(3).map(|(t, _)| t.to_string()).collect();
+        let shared: Vec<String> = ranked.iter()
+            .filter(|(_, (count, _))| *count * 2 >= group.len())
+            .take(2)
+            .map(|(t, _)| title_case(t))
+            .collect();
+        let label = if shared.is_empty() {
+            top_terms.first().map(|t| title_case(t)).unwrap_or_else(|| format!("Topic {}", clusters.len() + 1))
+        } else {
+            shared.join(" & ")
+        };
+
+        let pairs = group.len() * (group.len() - 1) / 2;
+        let cohesion = group.iter()
+            .enumerate()
+            .flat_map(|(x, i)| group[x + 1..].iter().map(|j| similarity[*i][*j]))
+            .sum::<f64>() / pairs as f64;
+
+        clusters.push(TopicCluster {
+            label,
+            assignment_ids: group.iter().map(|i| candidates[*i].id.clone()).collect(),
+            assignment_names: group.iter().map(|i| candidates[*i].name.clone()).collect(),
+            top_terms,
+            cohesion,
+        });
+    }
+    clusters.sort_by(|a, b| b.cohesion.partial_cmp(&a.cohesion).unwrap().then(a.label.cmp(&b.label)));
+
+    // Existing mapping plus one override per clustered assignment
+    let mut suggested_mapping = options.topics.clone();
+    for cluster in &clusters {
+        for id in &cluster.assignment_ids {
+            suggested_mapping.overrides.insert(id.clone(), vec![cluster.label.clone()]);
+        }
+    }
+
+    TopicClusterResult {
+        clusters,
+        unclustered,
+        considered: n,
+        suggested_mapping,
+    }
+}
+
+fn title_case(term: &str) -> String {
+    term.split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_chapter_difficulty;
+
+    #[test]
+    fn test_clusters_feed_chapter_difficulty() {
+        let names = [
+            ("A1", "Recursion Lab"),
+            ("A2", "Recursion Quiz"),
+            ("A3", "Big-O Quiz"),
+            ("A4", "Big-O Homework"),
+            ("A5", "Chapter 1 Quiz"),
+            ("A6", "Course survey"),
+        ];
+        let assignments: Vec<Assignment> = names.iter()
+            .map(|(id, name)| Assignment {
+                id: id.to_string(),
+                name: name.to_string(),
+                max_score: 100.0,
+                due_date: None,
+            })
+            .collect();
+        // Recursion items move together, as do Big-O items
+        let rows = [
+            ("S1", [90.0, 88.0, 50.0, 55.0, 80.0, 100.0]),
+            ("S2", [60.0, 62.0, 85.0, 80.0, 75.0, 100.0]),
+            ("S3", [75.0, 70.0, 65.0, 70.0, 70.0, 100.0]),
+            ("S4", [40.0, 45.0, 95.0, 90.0, 85.0, 100.0]),
+        ];
+        let grades: Vec<Grade> = rows.iter()
+            .flat_map(|(student, scores)| {
+                scores.iter().enumerate().map(move |(i, score)| Grade {
+                    student_id: student.to_string(),
+                    assignment_id: format!("A{}", i + 1),
+                    score: *score,
+                    max_score: 100.0,
+                    submitted_at: None,
+                    due_date: None,
+                })
+            })
+            .collect();
+
+        let options = TopicClusterOptions::default();
+        let result = cluster_topics(&grades, &assignments, &options, &options.topics.compile().unwrap());
+
+        assert_eq!(tokenize("Big-O Quizzes on Trees"), vec!["big-o", "tree"]);
+        assert_eq!(result.considered, 5);
+        assert_eq!(result.clusters.len(), 2);
+        let labels: Vec<&str> = result.clusters.iter().map(|c| c.label.as_str()).collect();
+        assert!(labels.contains(&"Recursion") && labels.contains(&"Big-O"));
+        assert_eq!(result.unclustered, vec!["A6".to_string()]);
+
+        // Accepted proposals flow into chapter difficulty as topic overrides
+        let mapper = result.suggested_mapping.compile().unwrap();
+        let difficulty = compute_chapter_difficulty(&grades, &assignments, &mapper, &Default::default());
+        let recursion = difficulty.chapters.iter().find(|c| c.chapter_name == "Recursion").unwrap();
+        assert_eq!(recursion.assignment_count, 2);
+        assert!(difficulty.chapters.iter().any(|c| c.chapter_name == "Chapter 1"));
+        assert_eq!(difficulty.unmapped_assignments.len(), 1);
+    }
+}
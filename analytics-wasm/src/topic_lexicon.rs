@@ -0,0 +1,223 @@
+// ============================================================================
+// Topic Lexicon (localized chapter prefixes and number words)
+// ============================================================================
+
+// Canonical topic kinds, in the order they are tried
+pub(crate) const KINDS: &[&str] = &["Chapter", "Unit", "Week", "Module", "Lecture", "Lesson", "Topic"];
+
+pub(crate) struct Locale {
+    pub code: &'static str,
+    pub prefixes: &'static [(&'static str, &'static [&'static str])],   // kind -> words
+    pub numbers: &'static [&'static str],                              // one, two, ... in order
+    pub sections: &'static [&'static str],                             // "Section 5.3" prefixes
+}
+
+// Hindi is transliterated, as it appears in Latin-script LMS exports
+pub(crate) const LOCALES: &[Locale] = &[
+    Locale {
+        code: "en",
+        prefixes: &[
+            ("Chapter", &["chapter", "chap", "ch"]),
+            ("Unit", &["unit"]),
+            ("Week", &["week", "wk"]),
+            ("Module", &["module", "mod"]),
+            ("Lecture", &["lecture", "lec"]),
+            ("Lesson", &["lesson"]),
+            ("Topic", &["topic"]),
+        ],
+        numbers: &[
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+            "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen",
+            "eighteen", "nineteen", "twenty",
+        ],
+        sections: &["section", "sect", "sec"],
+    },
+    Locale {
+        code: "de",
+        prefixes: &[
+            ("Chapter", &["kapitel", "kap"]),
+            ("Unit", &["einheit", "lerneinheit"]),
+            ("Week", &["woche"]),
+            ("Module", &["modul"]),
+            ("Lecture", &["vorlesung", "vl"]),
+            ("Lesson", &["lektion"]),
+            ("Topic", &["thema"]),
+        ],
+        numbers: &[
+            "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun", "zehn",
+            "elf", "zwölf", "dreizehn", "vierzehn", "fünfzehn", "sechzehn", "siebzehn",
+            "achtzehn", "neunzehn", "zwanzig",
+        ],
+        sections: &["abschnitt", "abschn"],
+    },
+    Locale {
+        code: "fr",
+        prefixes: &[
+            ("Chapter", &["chapitre"]),
+            ("Unit", &["unité", "unite"]),
+            ("Week", &["semaine", "sem"]),
+            ("Module", &[]),
+            ("Lecture", &["cours"]),
+            ("Lesson", &["leçon", "lecon"]),
+            ("Topic", &["thème", "theme", "sujet"]),
+        ],
+        numbers: &[
+            "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf", "dix",
+            "onze", "douze", "treize", "quatorze", "quinze", "seize", "dix-sept", "dix-huit",
+            "dix-neuf", "vingt",
+        ],
+        sections: &["section"],
+    },
+    Locale {
+        code: "es",
+        prefixes: &[
+            ("Chapter", &["capítulo", "capitulo", "cap"]),
+            ("Unit", &["unidad"]),
+            ("Week", &["semana"]),
+            ("Module", &["módulo", "modulo"]),
+            ("Lecture", &["clase"]),
+            ("Lesson", &["lección", "leccion"]),
+            ("Topic", &["tema"]),
+        ],
+        numbers: &[
+            "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve", "diez",
+            "once", "doce", "trece", "catorce", "quince", "dieciséis", "diecisiete",
+            "dieciocho", "diecinueve", "veinte",
+        ],
+        sections: &["sección", "seccion"],
+    },
+    Locale {
+        code: "pt",
+        prefixes: &[
+            ("Chapter", &["capítulo", "capitulo", "cap"]),
+            ("Unit", &["unidade"]),
+            ("Week", &["semana"]),
+            ("Module", &["módulo", "modulo"]),
+            ("Lecture", &["aula"]),
+            ("Lesson", &["lição", "licao"]),
+            ("Topic", &["tema", "tópico", "topico"]),
+        ],
+        numbers: &[
+            "um", "dois", "três", "quatro", "cinco", "seis", "sete", "oito", "nove", "dez",
+            "onze", "doze", "treze", "catorze", "quinze", "dezesseis", "dezessete", "dezoito",
+            "dezenove", "vinte",
+        ],
+        sections: &["seção", "secção", "secao"],
+    },
+    Locale {
+        code: "hi",
+        prefixes: &[
+            ("Chapter", &["adhyay", "adhyaya", "adhyaay"]),
+            ("Unit", &["ikai"]),
+            ("Week", &["saptah", "saptaah"]),
+            ("Module", &[]),
+            ("Lecture", &["vyakhyan"]),
+            ("Lesson", &["paath"]),
+            ("Topic", &["vishay"]),
+        ],
+        numbers: &[
+            "ek", "do", "teen", "chaar", "paanch", "chhah", "saat", "aath", "nau", "das",
+            "gyarah", "barah", "terah", "chaudah", "pandrah", "solah", "satrah", "atharah",
+            "unnis", "bees",
+        ],
+        sections: &["khand"],
+    },
+];
+
+pub(crate) fn locale(code: &str) -> Option<&'static Locale> {
+    LOCALES.iter().find(|l| l.code.eq_ignore_ascii_case(code))
+}
+
+// Prefix words for one kind across the given locales
+pub(crate) fn prefixes(locales: &[&Locale], kind: &str) -> Vec<&'static str> {
+    let mut words: Vec<&'static str> = locales.iter()
+        .flat_map(|l| l.prefixes.iter().filter(|(k, _)| *k == kind).flat_map(|(_, w)| w.iter().copied()))
+        .collect();
+    // Longest first so "chapter" wins over "ch" in the alternation
+    words.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then(a.cmp(b)));
+    words.dedup();
+    words
+}
+
+// Section prefix words across the given locales, longest first
+pub(crate) fn section_prefixes(locales: &[&Locale]) -> Vec<&'static str> {
+    let mut words: Vec<&'static str> = locales.iter().flat_map(|l| l.sections.iter().copied()).collect();
+    words.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then(a.cmp(b)));
+    words.dedup();
+    words
+}
+
+// "3", "03", "III", "iii", "three", "drei" -> 3
+pub(crate) fn parse_number(token: &str, locales: &[&Locale]) -> Option<u32> {
+    if token.chars().all(|c| c.is_ascii_digit()) {
+        return token.parse().ok();
+    }
+    let lower = token.to_lowercase();
+    if let Some(value) = locales.iter()
+        .find_map(|l| l.numbers.iter().position(|w| *w == lower))
+    {
+        return Some(value as u32 + 1);
+    }
+    parse_roman(token)
+}
+
+// Strict Roman numerals up to 100, all upper or all lower case
+fn parse_roman(token: &str) -> Option<u32> {
+    if token.is_empty() || (token != token.to_uppercase() && token != token.to_lowercase()) {
+        return None;
+    }
+    let upper = token.to_uppercase();
+    let digit = |c: char| match c {
+        'I' => Some(1),
+        'V' => Some(5),
+        'X' => Some(10),
+        'L' => Some(50),
+        'C' => Some(100),
+        _ => None,
+    };
+    let values: Vec<u32> = upper.chars().map(digit).collect::<Option<_>>()?;
+    let mut total = 0;
+    for (i, value) in values.iter().enumerate() {
+        match values.get(i + 1) {
+            Some(next) if next > value => total -= *value as i64,
+            _ => total += *value as i64,
+        }
+    }
+    let total = u32::try_from(total).ok().filter(|t| (1..=100).contains(t))?;
+    // Reject non-canonical spellings such as "IIII" or "VX"
+    (to_roman(total) == upper).then_some(total)
+}
+
+fn to_roman(mut value: u32) -> String {
+    let table = [
+        (100, "C"), (90, "XC"), (50, "L"), (40, "XL"),
+        (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+    let mut roman = String::new();
+    for (amount, symbol) in table {
+        while value >= amount {
+            roman.push_str(symbol);
+            value -= amount;
+        }
+    }
+    roman
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        let all: Vec<&Locale> = LOCALES.iter().collect();
+        assert_eq!(parse_number("03", &all), Some(3));
+        assert_eq!(parse_number("IV", &all), Some(4));
+        assert_eq!(parse_number("xii", &all), Some(12));
+        assert_eq!(parse_number("Drei", &all), Some(3));
+        assert_eq!(parse_number("dix-sept", &all), Some(17));
+        assert_eq!(parse_number("teen", &all), Some(3));
+        assert_eq!(parse_number("IIII", &all), None);
+        assert_eq!(parse_number("Mix", &all), None);
+        assert_eq!(parse_number("Intro", &all), None);
+    }
+}
@@ -0,0 +1,222 @@
+// ============================================================================
+// Topic Tree (unit > chapter > section roll-up of difficulty)
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dates::assignment_timeline;
+use crate::topics::{topic_level, TopicMapper, UNMAPPED_TOPIC};
+use crate::{calculate_mean, calculate_std_deviation, categorize_difficulty, Assignment, Grade};
+
+pub(crate) const LEVELS: &[&str] = &["unit", "chapter", "section"];
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TopicNode {
+    pub key: String,                  // Full path, e.g. "Unit 2 › Chapter 5"
+    pub name: String,
+    pub level: String,                // "unit", "chapter", "section"
+    pub avg_score: f64,               // Over every grade in the subtree
+    pub std_deviation: f64,
+    pub difficulty_level: String,
+    pub assignment_count: usize,
+    pub student_count: usize,
+    pub assignment_ids: Vec<String>,  // Assigned directly to this node
+    pub hardest_child: Option<String>,
+    pub easiest_child: Option<String>,
+    pub children: Vec<TopicNode>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TopicLevelSummary {
+    pub level: String,
+    pub node_count: usize,
+    pub hardest: Option<String>,      // Node key
+    pub easiest: Option<String>,
+}
+
+// Node under construction; children keep first-due order
+#[derive(Default)]
+struct Branch {
+    direct: Vec<String>,
+    children: Vec<(String, Branch)>,
+}
+
+impl Branch {
+    fn insert(&mut self, path: &[String], assignment_id: &str) {
+        let Some((head, rest)) = path.split_first() else {
+            if !self.direct.iter().any(|id| id == assignment_id) {
+                self.direct.push(assignment_id.to_string());
+            }
+            return;
+        };
+        let position = match self.children.iter().position(|(name, _)| name == head) {
+            Some(position) => position,
+            None => {
+                self.children.push((head.clone(), Branch::default()));
+                self.children.len() - 1
+            }
+        };
+        self.children[position].1.insert(rest, assignment_id);
+    }
+
+    fn subtree_assignments(&self, into: &mut BTreeSet<String>) {
+        into.extend(self.direct.iter().cloned());
+        for (_, child) in &self.children {
+            child.subtree_assignments(into);
+        }
+    }
+}
+
+pub(crate) fn build_topic_tree(
+    grades: &[Grade],
+    assignments: &[Assignment],
+    topics: &TopicMapper,
+) -> (Vec<TopicNode>, Vec<TopicLevelSummary>) {
+    let mut root = Branch::default();
+    for (assignment_id, _) in assignment_timeline(grades, assignments) {
+        if let Some(assignment) = assignments.iter().find(|a| a.id == assignment_id) {
+            for path in topics.topic_paths(assignment) {
+                root.insert(&path, &assignment.id);
+            }
+        }
+    }
+
+    let nodes: Vec<TopicNode> = root.children.iter()
+        .map(|(name, branch)| build_node(grades, name, name, branch))
+        .collect();
+
+    let mut by_level: BTreeMap<usize, Vec<&TopicNode>> = BTreeMap::new();
+    let mut stack: Vec<&TopicNode> = nodes.iter().collect();
+    while let Some(node) = stack.pop() {
+        let level = LEVELS.iter().position(|l| *l == node.level).unwrap_or(1);
+        by_level.entry(level).or_default().push(node);
+        stack.extend(node.children.iter());
+    }
+    let levels = by_level.iter()
+        .map(|(level, members)| {
+            let (hardest, easiest) = extremes(members.iter().copied());
+            TopicLevelSummary {
+                level: LEVELS[*level].to_string(),
+                node_count: members.len(),
+                hardest,
+                easiest,
+            }
+        })
+        .collect();
+
+    (nodes, levels)
+}
+
+fn build_node(grades: &[Grade], key: &str, name: &str, branch: &Branch) -> TopicNode {
+    let children: Vec<TopicNode> = branch.children.iter()
+        .map(|(child, sub)| build_node(grades, &format!("{} › {}", key, child), child, sub))
+        .collect();
+
+    let mut assignment_ids = BTreeSet::new();
+    branch.subtree_assignments(&mut assignment_ids);
+    let subtree_grades: Vec<&Grade> = grades.iter()
+        .filter(|g| g.max_score > 0.0 && assignment_ids.contains(&g.assignment_id))
+        .collect();
+    let scores: Vec<f64> = subtree_grades.iter().map(|g| (g.score / g.max_score) * 100.0).collect();
+    let students: BTreeSet<&str> = subtree_grades.iter().map(|g| g.student_id.as_str()).collect();
+    let avg = calculate_mean(&scores);
+    let std_dev = calculate_std_deviation(&scores, avg);
+
+    let (hardest_child, easiest_child) = if children.iter().filter(|c| c.name != UNMAPPED_TOPIC).count() >= 2 {
+        extremes(children.iter())
+    } else {
+        (None, None)
+    };
+
+    TopicNode {
+        key: key.to_string(),
+        name: name.to_string(),
+        level: LEVELS[topic_level(name)].to_string(),
+        avg_score: avg,
+        std_deviation: std_dev,
+        difficulty_level: categorize_difficulty(avg, std_dev),
+        assignment_count: assignment_ids.len(),
+        student_count: students.len(),
+        assignment_ids: branch.direct.clone(),
+        hardest_child,
+        easiest_child,
+        children,
+    }
+}
+
+// Lowest and highest average among graded nodes; "Other" is not a topic to rank
+fn extremes<'a>(nodes: impl Iterator<Item = &'a TopicNode>) -> (Option<String>, Option<String>) {
+    let mut graded: Vec<&TopicNode> = nodes
+        .filter(|n| n.student_count > 0 && n.name != UNMAPPED_TOPIC)
+        .collect();
+    graded.sort_by(|a, b| a.avg_score.partial_cmp(&b.avg_score).unwrap().then(a.key.cmp(&b.key)));
+    (
+        graded.first().map(|n| n.key.clone()),
+        graded.last().map(|n| n.key.clone()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topics::TopicMapping;
+
+    #[test]
+    fn test_tree_rolls_up_sections_to_units() {
+        let names = [
+            ("A1", "Section 5.1 Quiz"),
+            ("A2", "Section 5.3 Quiz"),
+            ("A3", "Chapter 6 Quiz"),
+            ("A4", "Unit 3 Exam"),
+            ("A5", "Course survey"),
+        ];
+        let assignments: Vec<Assignment> = names.iter()
+            .map(|(id, name)| Assignment {
+                id: id.to_string(),
+                name: name.to_string(),
+                max_score: 100.0,
+                due_date: None,
+            })
+            .collect();
+        let scores = [("A1", 90.0), ("A2", 50.0), ("A3", 80.0), ("A4", 60.0), ("A5", 100.0)];
+        let grades: Vec<Grade> = scores.iter()
+            .map(|(assignment_id, score)| Grade {
+                student_id: "S1".to_string(),
+                assignment_id: assignment_id.to_string(),
+                score: *score,
+                max_score: 100.0,
+                submitted_at: None,
+                due_date: None,
+            })
+            .collect();
+        let mapping: TopicMapping = serde_json::from_str(
+            r#"{"parents": {"Chapter 5": "Unit 2", "Chapter VI": "Unit 2"}}"#,
+        ).unwrap();
+
+        let (tree, levels) = build_topic_tree(&grades, &assignments, &mapping.compile().unwrap());
+
+        assert_eq!(tree.len(), 3);
+        let unit_2 = &tree[0];
+        assert_eq!(unit_2.name, "Unit 2");
+        assert_eq!(unit_2.level, "unit");
+        assert_eq!(unit_2.assignment_count, 3);
+        assert!((unit_2.avg_score - 220.0 / 3.0).abs() < 1e-9);
+        assert_eq!(unit_2.hardest_child.as_deref(), Some("Unit 2 › Chapter 5"));
+
+        let chapter_5 = &unit_2.children[0];
+        assert_eq!(chapter_5.children.len(), 2);
+        assert_eq!(chapter_5.children[1].key, "Unit 2 › Chapter 5 › Section 5.3");
+        assert_eq!(chapter_5.children[1].assignment_ids, vec!["A2".to_string()]);
+
+        let section_level = levels.iter().find(|l| l.level == "section").unwrap();
+        assert_eq!(section_level.hardest.as_deref(), Some("Unit 2 › Chapter 5 › Section 5.3"));
+        let chapter_level = levels.iter().find(|l| l.level == "chapter").unwrap();
+        assert_eq!(chapter_level.easiest.as_deref(), Some("Unit 2 › Chapter 6"));
+        let unit_level = levels.iter().find(|l| l.level == "unit").unwrap();
+        assert_eq!(unit_level.hardest.as_deref(), Some("Unit 3"));
+        assert!(tree.iter().any(|n| n.name == "Other" && n.assignment_ids == vec!["A5".to_string()]));
+        assert_eq!(mapping.compile().unwrap().name_topics("Section 5.3 Quiz"), vec!["Chapter 5"]);
+    }
+}
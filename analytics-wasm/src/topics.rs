@@ -0,0 +1,363 @@
+// ============================================================================
+// Topic Mapping (assignment -> chapter/topic via patterns and overrides)
+// ============================================================================
+
+use std::collections::BTreeMap;
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::topic_lexicon::{self, Locale};
+use crate::Assignment;
+
+pub(crate) const UNMAPPED_TOPIC: &str = "Other";
+
+// Prefixes that have always accepted named chapters ("Chapter Review")
+const NAMED_PREFIXES: &[&str] = &["Chapter", "Ch", "Unit", "Week", "Module"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopicPattern {
+    pub pattern: String,                  // Regex matched against the assignment name
+    #[serde(default)]
+    pub topic: Option<String>,            // Template such as "Chapter $1"; defaults to group 1
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TopicMapping {
+    pub patterns: Vec<TopicPattern>,
+    pub overrides: BTreeMap<String, Vec<String>>,   // assignment_id -> topics, beats patterns
+    pub parents: BTreeMap<String, String>,          // topic -> enclosing topic ("Chapter 5" -> "Unit 2")
+    pub use_default_patterns: bool,
+    pub locales: Vec<String>,             // Lexicons added to English; empty means all
+    pub canonicalize: bool,               // "Kapitel III" and "Chapter 3" share a key
+}
+
+impl Default for TopicMapping {
+    fn default() -> Self {
+        TopicMapping {
+            patterns: Vec::new(),
+            overrides: BTreeMap::new(),
+            parents: BTreeMap::new(),
+            use_default_patterns: true,
+            locales: Vec::new(),
+            canonicalize: true,
+        }
+    }
+}
+
+impl TopicMapping {
+    pub(crate) fn compile(&self) -> Result<TopicMapper, String> {
+        let patterns = self.patterns.iter()
+            .map(|p| {
+                let regex = Regex::new(&p.pattern)
+                    .map_err(|e| format!("Invalid topic pattern '{}': {}", p.pattern, e))?;
+                let template = p.topic.clone().unwrap_or_else(|| {
+                    if regex.captures_len() > 1 { "$1".to_string() } else { "$0".to_string() }
+                });
+                Ok((regex, template))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let locales: Vec<&'static Locale> = if self.locales.is_empty() {
+            topic_lexicon::LOCALES.iter().collect()
+        } else {
+            std::iter::once("en").chain(self.locales.iter().map(|code| code.as_str()))
+                .map(|code| {
+                    topic_lexicon::locale(code).ok_or_else(|| {
+                        let known: Vec<&str> = topic_lexicon::LOCALES.iter().map(|l| l.code).collect();
+                        format!("Unknown topic locale '{}' (expected one of {})", code, known.join(", "))
+                    })
+                })
+                .collect::<Result<_, String>>()?
+        };
+
+        // One case-insensitive matcher per topic kind capturing prefix, separator and id
+        let builtins = topic_lexicon::KINDS.iter()
+            .filter_map(|kind| {
+                let words = topic_lexicon::prefixes(&locales, kind);
+                if words.is_empty() {
+                    return None;
+                }
+                let alternation: Vec<String> = words.iter().map(|w| regex::escape(w)).collect();
+                let pattern = format!(r"(?i)\b({})(\.\s*|\s+)?(\w+(?:-\w+)*)", alternation.join("|"));
+                Some((kind.to_string(), Regex::new(&pattern).unwrap()))
+            })
+            .collect();
+
+        let section_words: Vec<String> = topic_lexicon::section_prefixes(&locales).iter()
+            .map(|w| regex::escape(w))
+            .collect();
+        let sections = Regex::new(&format!(
+            r"(?i)(?:\b(?:{})\.?\s*|§\s*)(\d+(?:\.\d+)*)",
+            section_words.join("|"),
+        )).unwrap();
+
+        let mut mapper = TopicMapper {
+            overrides: self.overrides.clone(),
+            parents: BTreeMap::new(),
+            patterns,
+            builtins,
+            sections,
+            use_builtins: self.use_default_patterns,
+            canonicalize: self.canonicalize,
+            locales,
+        };
+        mapper.parents = self.parents.iter()
+            .map(|(child, parent)| (mapper.canonical(child), mapper.canonical(parent)))
+            .collect();
+        Ok(mapper)
+    }
+}
+
+pub(crate) struct TopicMapper {
+    overrides: BTreeMap<String, Vec<String>>,
+    parents: BTreeMap<String, String>,
+    patterns: Vec<(Regex, String)>,
+    builtins: Vec<(String, Regex)>,
+    sections: Regex,
+    use_builtins: bool,
+    canonicalize: bool,
+    locales: Vec<&'static Locale>,
+}
+
+impl TopicMapper {
+    // Topics for an assignment; empty when nothing maps it
+    pub(crate) fn topics(&self, assignment: &Assignment) -> Vec<String> {
+        match self.overrides.get(&assignment.id) {
+            Some(topics) => dedup(topics.iter().map(|t| self.canonical(t))),
+            None => self.name_topics(&assignment.name),
+        }
+    }
+
+    // Like topics(), with unmapped assignments grouped under "Other"
+    pub(crate) fn topics_or_other(&self, assignment: &Assignment) -> Vec<String> {
+        let topics = self.topics(assignment);
+        if topics.is_empty() { vec![UNMAPPED_TOPIC.to_string()] } else { topics }
+    }
+
+    // Every user pattern contributes; built-ins only as a fallback, first match wins
+    pub(crate) fn name_topics(&self, name: &str) -> Vec<String> {
+        let user = dedup(self.patterns.iter().flat_map(|p| expand_all(p, name)).map(|t| self.canonical(&t)));
+        if !user.is_empty() || !self.use_builtins {
+            return user;
+        }
+        self.builtins.iter()
+            .map(|(kind, regex)| {
+                dedup(regex.captures_iter(name).filter_map(|caps| self.builtin_topic(kind, &caps)))
+            })
+            .find(|topics| !topics.is_empty())
+            .unwrap_or_else(|| {
+                dedup(self.sections.captures_iter(name).map(|caps| self.section_chapter(&format!("Section {}", &caps[1]))))
+            })
+    }
+
+    // Sections imply their chapter ("Section 5.3" -> "Chapter 5") unless a parent is given
+    fn section_chapter(&self, section: &str) -> String {
+        self.parents.get(section).cloned().unwrap_or_else(|| {
+            let number = section.trim_start_matches("Section ");
+            format!("Chapter {}", number.split('.').next().unwrap_or(number))
+        })
+    }
+
+    // "Kapitel III" -> "Chapter 3"; ids that are not numbers only under the named English prefixes
+    fn builtin_topic(&self, kind: &str, caps: &Captures) -> Option<String> {
+        let (prefix, id) = (&caps[1], &caps[3]);
+        // "Ch3" is fine, "Chemistry" is not
+        if caps.get(2).is_none() && !id.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let number = topic_lexicon::parse_number(id, &self.locales).or_else(|| {
+            let head = id.split('-').next()?;
+            topic_lexicon::parse_number(head, &self.locales)
+        });
+        match number {
+            Some(number) if self.canonicalize => Some(format!("{} {}", kind, number)),
+            Some(_) => Some(format!("{} {}", kind, id)),
+            None if NAMED_PREFIXES.contains(&prefix) => Some(format!("{} {}", kind, id.split('-').next()?)),
+            None => None,
+        }
+    }
+
+    // Paths from the top level down, e.g. ["Unit 2", "Chapter 5", "Section 5.3"]
+    pub(crate) fn topic_paths(&self, assignment: &Assignment) -> Vec<Vec<String>> {
+        let labels = match self.overrides.get(&assignment.id) {
+            Some(topics) => dedup(topics.iter().map(|t| self.canonical(t))),
+            None => {
+                // Unlike name_topics(), every built-in kind counts so "Unit 2 Chapter 5" keeps both
+                let mut labels = dedup(self.patterns.iter().flat_map(|p| expand_all(p, &assignment.name)).map(|t| self.canonical(&t)));
+                if labels.is_empty() && self.use_builtins {
+                    labels = dedup(self.builtins.iter().flat_map(|(kind, regex)| {
+                        regex.captures_iter(&assignment.name)
+                            .filter_map(|caps| self.builtin_topic(kind, &caps))
+                            .collect::<Vec<_>>()
+                    }));
+                }
+                labels.extend(self.sections.captures_iter(&assignment.name).map(|caps| format!("Section {}", &caps[1])));
+                dedup(labels.into_iter())
+            }
+        };
+
+        let at_level = |level: usize| -> Vec<String> {
+            labels.iter().filter(|l| topic_level(l) == level).cloned().collect()
+        };
+        let (units, mut chapters, sections) = (at_level(0), at_level(1), at_level(2));
+
+        if chapters.is_empty() {
+            chapters = dedup(sections.iter().map(|s| self.section_chapter(s)));
+        }
+        if chapters.is_empty() {
+            if units.is_empty() {
+                return vec![vec![UNMAPPED_TOPIC.to_string()]];
+            }
+            return units.into_iter().map(|u| vec![u]).collect();
+        }
+
+        let mut paths = Vec::new();
+        for chapter in &chapters {
+            let unit = self.parents.get(chapter).cloned().or_else(|| units.first().cloned());
+            let prefix: Vec<String> = unit.into_iter().chain(std::iter::once(chapter.clone())).collect();
+            let children: Vec<&String> = sections.iter()
+                .filter(|s| self.section_chapter(s) == *chapter)
+                .collect();
+            if children.is_empty() {
+                paths.push(prefix);
+            } else {
+                for section in children {
+                    paths.push(prefix.iter().cloned().chain(std::iter::once(section.clone())).collect());
+                }
+            }
+        }
+        paths
+    }
+
+    // Canonical key for a label from an override, a user pattern or an import
+    pub(crate) fn canonical(&self, label: &str) -> String {
+        let label = label.trim();
+        if let Some(caps) = self.sections.captures(label)
+            && caps[0].len() == label.len()
+        {
+            return format!("Section {}", &caps[1]);
+        }
+        if self.canonicalize {
+            for (kind, regex) in &self.builtins {
+                if let Some(caps) = regex.captures(label)
+                    && caps[0].len() == label.len()
+                    && let Some(topic) = self.builtin_topic(kind, &caps)
+                {
+                    return topic;
+                }
+            }
+        }
+        label.to_string()
+    }
+}
+
+// 0 = unit, 1 = chapter, 2 = section
+pub(crate) fn topic_level(label: &str) -> usize {
+    if label.starts_with("Section ") {
+        2
+    } else if label.starts_with("Unit ") || label.starts_with("Module ") {
+        0
+    } else {
+        1
+    }
+}
+
+fn expand_all(pattern: &(Regex, String), name: &str) -> Vec<String> {
+    let (regex, template) = pattern;
+    regex.captures_iter(name)
+        .map(|caps| {
+            let mut topic = String::new();
+            caps.expand(template, &mut topic);
+            topic.trim().to_string()
+        })
+        .filter(|topic| !topic.is_empty())
+        .collect()
+}
+
+fn dedup(topics: impl Iterator<Item = String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for topic in topics.filter(|t| !t.is_empty()) {
+        if !unique.contains(&topic) {
+            unique.push(topic);
+        }
+    }
+    unique
+}
+
+// Built-in patterns only; entry points compile the caller's mapping instead
+#[cfg(test)]
+pub(crate) fn default_mapper() -> &'static TopicMapper {
+    static MAPPER: std::sync::OnceLock<TopicMapper> = std::sync::OnceLock::new();
+    MAPPER.get_or_init(|| TopicMapping::default().compile().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignment(id: &str, name: &str) -> Assignment {
+        Assignment {
+            id: id.to_string(),
+            name: name.to_string(),
+            max_score: 100.0,
+            due_date: None,
+        }
+    }
+
+    #[test]
+    fn test_patterns_overrides_and_multiple_topics() {
+        let mapping: TopicMapping = serde_json::from_str(r#"{
+            "patterns": [
+                {"pattern": "(?i)\\bPS\\s*(\\d+)", "topic": "Problem Set $1"},
+                {"pattern": "(?i)\\b(recursion|sorting)\\b"}
+            ],
+            "overrides": {"A9": ["Chapter 2", "Chapter 3"]}
+        }"#).unwrap();
+        let mapper = mapping.compile().unwrap();
+
+        assert_eq!(mapper.topics(&assignment("A1", "ps 4: Recursion")), vec!["Problem Set 4", "Recursion"]);
+        assert_eq!(mapper.topics(&assignment("A9", "Midterm")), vec!["Chapter 2", "Chapter 3"]);
+        assert_eq!(mapper.topics(&assignment("A2", "Ch.3 Quiz")), vec!["Chapter 3"]);
+        assert_eq!(mapper.topics(&assignment("A3", "Lecture 4 reflection")), vec!["Lecture 4"]);
+        assert_eq!(mapper.topics(&assignment("A4", "Chapter 1 and Chapter 2 Review")), vec!["Chapter 1", "Chapter 2"]);
+        assert!(mapper.topics(&assignment("A5", "Chemistry safety form")).is_empty());
+        assert_eq!(mapper.topics_or_other(&assignment("A5", "Syllabus")), vec![UNMAPPED_TOPIC]);
+
+        let invalid = TopicMapping {
+            patterns: vec![TopicPattern { pattern: "(".to_string(), topic: None }],
+            ..TopicMapping::default()
+        };
+        assert!(invalid.compile().is_err());
+    }
+
+    #[test]
+    fn test_multilingual_and_numeral_topics() {
+        let mapper = default_mapper();
+        let topic = |name: &str| mapper.name_topics(name);
+
+        assert_eq!(topic("Kapitel 3 Test"), vec!["Chapter 3"]);
+        assert_eq!(topic("Chapitre IV"), vec!["Chapter 4"]);
+        assert_eq!(topic("Chapter III"), topic("Chapter 3"));
+        assert_eq!(topic("Capítulo dos - Ejercicios"), vec!["Chapter 2"]);
+        assert_eq!(topic("Tema 2"), vec!["Topic 2"]);
+        assert_eq!(topic("Adhyay teen quiz"), vec!["Chapter 3"]);
+        assert_eq!(topic("semaine dix-sept"), vec!["Week 17"]);
+        assert_eq!(topic("Ch3 review"), vec!["Chapter 3"]);
+        assert_eq!(topic("Chapter Review"), vec!["Chapter Review"]);
+        assert!(topic("Chemistry lab").is_empty());
+        assert!(topic("Aula Virtual").is_empty());
+
+        let mapping: TopicMapping = serde_json::from_str(
+            r#"{"locales": ["de"], "overrides": {"A1": ["Kapitel II"]}}"#,
+        ).unwrap();
+        let german = mapping.compile().unwrap();
+        assert_eq!(german.topics(&assignment("A1", "Bonus")), vec!["Chapter 2"]);
+        assert!(german.name_topics("Chapitre 4").is_empty());
+        assert_eq!(german.name_topics("Chapter five"), vec!["Chapter 5"]);
+
+        let unknown: TopicMapping = serde_json::from_str(r#"{"locales": ["xx"]}"#).unwrap();
+        assert!(unknown.compile().is_err());
+    }
+}